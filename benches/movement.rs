@@ -0,0 +1,91 @@
+//! Timings for the movement hot path: crawling, walking a worm's cells, occupancy, and whole game steps.
+//!
+//! Run with:
+//! ```text
+//! cargo bench --bench movement --no-default-features
+//! ```
+//!
+//! This is a plain timed loop rather than a statistical harness, so compare numbers from the same machine only.
+//! Each line prints the average time per iteration next to the baseline measured when the benchmark was added (x86-64 Linux, release profile);
+//! a result several times the baseline is worth a look.
+
+use std::{hint::black_box, time::{Duration, Instant}};
+use puzzle_game_rs::{
+    spacial::{direction3::Direction3, vector3i::Vector3i},
+    worm::Worm,
+    level::Level,
+    game_state::GameState,
+    rules::{ruleset::Ruleset, word::{noun::Noun, property::Property}},
+};
+
+/// Run `f` repeatedly for about 200ms and print the average time per call beside `baseline_ns`.
+fn bench(name: &str, baseline_ns: f64, mut f: impl FnMut()) {
+    let budget = Duration::from_millis(200);
+    // Warm up caches
+    f();
+    let start = Instant::now();
+    let mut iterations = 0u64;
+    while start.elapsed() < budget {
+        f();
+        iterations += 1;
+    }
+    let per_iter = start.elapsed().as_nanos() as f64 / iterations as f64;
+    println!("{name:<32} {per_iter:>12.1} ns/iter   (baseline {baseline_ns:>10.1} ns/iter, {iterations} iterations)");
+}
+
+/// A straight worm `len` cells long, head at the origin and body trailing west.
+fn long_worm(len: usize) -> Worm {
+    Worm::new(Vector3i::new(0, 0, 0), std::iter::repeat_n(Direction3::West, len - 1))
+}
+
+/// An open square level with a border of walls and one YOU worm of `len` cells lying along the bottom.
+fn generated_game(size: i32, len: usize) -> GameState {
+    let mut level = Level::new(Vector3i::new(size, size, 1));
+    for i in 0..size {
+        level.add_wall(Vector3i::new(i, 0, 0));
+        level.add_wall(Vector3i::new(i, size - 1, 0));
+        level.add_wall(Vector3i::new(0, i, 0));
+        level.add_wall(Vector3i::new(size - 1, i, 0));
+    }
+    level.add_worm(Worm::new(Vector3i::new(len as i32, 1, 0), std::iter::repeat_n(Direction3::West, len - 1)));
+    GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]))
+}
+
+fn main() {
+    for (len, baselines) in [(1_000, [45.0, 4_600.0, 67_000.0, 67.0]), (10_000, [47.0, 45_000.0, 840_000.0, 88.0])] {
+        let [crawl, positions, occupancy_cold, occupancy_warm] = baselines;
+
+        let mut worm = long_worm(len);
+        let mut turn = [Direction3::North, Direction3::East, Direction3::South, Direction3::East].into_iter().cycle();
+        bench(&format!("crawl/{len}"), crawl, || worm.crawl(turn.next().unwrap()));
+
+        let worm = long_worm(len);
+        let mut buf = Vec::new();
+        bench(&format!("collect_positions_into/{len}"), positions, || {
+            worm.collect_positions_into(&mut buf);
+            black_box(&buf);
+        });
+
+        let mut worm = long_worm(len);
+        let far = Vector3i::new(-(len as i32) / 2, 0, 0);
+        bench(&format!("occupies after crawl/{len}"), occupancy_cold, || {
+            // Crawling clears the cached cells, so this measures rebuilding them
+            worm.crawl(Direction3::East);
+            black_box(worm.occupies(black_box(far)));
+        });
+
+        let worm = long_worm(len);
+        bench(&format!("occupies cached/{len}"), occupancy_warm, || {
+            black_box(worm.occupies(black_box(far)));
+        });
+    }
+
+    let start = generated_game(64, 40);
+    let path = [Direction3::North, Direction3::East, Direction3::South, Direction3::East];
+    bench("GameState::step x40 64x64, 40 cells", 890_000.0, || {
+        let mut game = start.clone();
+        for &direction in path.iter().cycle().take(40) {
+            black_box(game.step(direction));
+        }
+    });
+}
@@ -0,0 +1,81 @@
+//! Plays a short, scripted puzzle without opening a window.
+//!
+//! Run with:
+//! ```text
+//! cargo run --example headless --no-default-features
+//! ```
+//!
+//! Nothing here touches raylib, so it works in headless environments like CI.
+
+use puzzle_game_rs::{
+    spacial::direction3::Direction3,
+    level::Level,
+    game_state::GameState,
+    rules::{ruleset::Ruleset, word::{noun::Noun, property::Property}},
+};
+
+/// The level, in the format described in `puzzle_game_rs::level::parse`.
+///
+/// ```text
+///   y
+///   2 . . . . G .
+///   1 . # # . # .
+///   0 - o . . . .
+///     0 1 2 3 4 5 x
+/// ```
+const LEVEL: &str = "
+    bounds 6 3 1
+    wall 1 1 0
+    wall 2 1 0
+    wall 4 1 0
+    goal 4 2 0
+    worm 1 0 0 <
+";
+
+/// The moves that solve [`LEVEL`].
+const INPUTS: [Direction3; 5] = [
+    Direction3::East,
+    Direction3::East,
+    Direction3::North,
+    Direction3::North,
+    Direction3::East,
+];
+
+/// Plays [`INPUTS`] on [`LEVEL`], printing what happened after each step.
+fn run() -> GameState {
+    // 1. Parse the level. This also validates it, so a typo in the level is caught here.
+    let level: Level = LEVEL.parse().expect("LEVEL should be valid");
+
+    // 2. Choose the rules. Without `WORM IS YOU` nothing would move,
+    //    and without `GOAL IS WIN` reaching the goal would do nothing.
+    let rules = Ruleset::from_iter([
+        (Noun::WORM, Property::YOU),
+        (Noun::GOAL, Property::WIN),
+    ]);
+
+    // 3. Bundle them into a game.
+    let mut game = GameState::new(level, rules);
+
+    // 4. Feed it input, one step at a time. Each step reports what changed.
+    for (tick, direction) in INPUTS.into_iter().enumerate() {
+        let report = game.step(direction);
+        println!("step {tick}: {direction:?} => {report:?}");
+    }
+
+    game
+}
+
+fn main() {
+    let game = run();
+    println!("won: {}", game.is_won());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solves() {
+        assert!(run().is_won(), "scripted inputs should solve the level");
+    }
+}
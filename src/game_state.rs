@@ -0,0 +1,570 @@
+//! The complete state of a puzzle in progress, independent of rendering.
+
+use std::{collections::{VecDeque, BTreeMap}, fmt, hash::{Hash, Hasher}};
+use crate::{
+    spacial::direction3::Direction3,
+    level::{Level, Object, WormId},
+    worm::Worm,
+    rules::{ruleset::Ruleset, word::{noun::Noun, property::Property}},
+};
+
+pub mod parse;
+
+#[cfg(test)]
+mod tests;
+
+/// What happened during a single [`GameState::step()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StepReport {
+    /// [`Property::YOU`] worms that crawled.
+    pub moved: Vec<WormId>,
+    /// Worms destroyed by [`Property::DEFEAT`].
+    pub destroyed: Vec<WormId>,
+    /// Worms shoved by a [`Property::YOU`] worm crawling into them.
+    pub pushed: Vec<WormId>,
+    /// The level is won after this step.
+    pub won: bool,
+    /// The input direction, if any [`Property::YOU`] worm could not crawl in it.
+    pub blocked: Option<Direction3>,
+}
+
+/// A compact, multiline summary of `report` for debug overlays: one `field: value` line per field of [`StepReport`].
+/// Worms are listed by id, and `-` stands for an empty list or no blocked direction.
+///
+/// Example:
+/// ```
+/// # use puzzle_game_rs::game_state::{StepReport, format_report};
+/// assert_eq!(format_report(&StepReport::default()), "moved: -\ndestroyed: -\npushed: -\nwon: false\nblocked: -");
+/// ```
+pub fn format_report(report: &StepReport) -> String {
+    let ids = |ids: &[WormId]| if ids.is_empty() {
+        "-".to_string()
+    } else {
+        ids.iter().map(WormId::to_string).collect::<Vec<_>>().join(", ")
+    };
+    format!(
+        "moved: {}\ndestroyed: {}\npushed: {}\nwon: {}\nblocked: {}",
+        ids(&report.moved),
+        ids(&report.destroyed),
+        ids(&report.pushed),
+        report.won,
+        report.blocked.map_or("-".to_string(), |direction| format!("{direction:?}")),
+    )
+}
+
+/// One thing that happened during a step, as passed to the listener set with [`GameState::set_listener()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameEvent {
+    /// The [`Property::YOU`] worm crawled, like [`StepReport::moved`].
+    /// Pushed worms only get [`GameEvent::Pushed`], and [`Property::MOVE`] worms crawling on their own get no event.
+    Moved {
+        /// The worm.
+        id: WormId,
+    },
+    /// The worm was shoved, like [`StepReport::pushed`].
+    Pushed {
+        /// The worm.
+        id: WormId,
+    },
+    /// The worm touched something [`Property::DEFEAT`] and is about to be destroyed by it.
+    /// Worms that are [`Property::DEFEAT`] themselves are destroyed without being eaten.
+    Eaten {
+        /// The first thing in the worm's cells, in the order of [`Level::objects_at()`], that destroys it.
+        eater: Object,
+        /// The worm.
+        eaten: WormId,
+    },
+    /// The worm was destroyed, like [`StepReport::destroyed`].
+    Destroyed {
+        /// The worm.
+        id: WormId,
+    },
+    /// The level is won after the step.
+    Won,
+}
+
+/// The listener set with [`GameState::set_listener()`].
+/// Clones of a game start without one, so copies made to search or preview moves stay silent.
+#[derive(Default)]
+struct Listener(Option<Box<ListenerFn>>);
+
+type ListenerFn = dyn FnMut(&GameEvent) + Send + Sync;
+
+impl Clone for Listener {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+impl fmt::Debug for Listener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() { "Listener(..)" } else { "Listener(None)" })
+    }
+}
+
+/// How [`GameState::step_all_you()`] settles [`Property::YOU`] worms that would end up in the same cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflictPolicy {
+    /// Every worm in a conflict stays where it is; the rest still move.
+    CancelAll,
+    /// The worm with the lowest id in a conflict moves and the other stays where it is.
+    /// If the winner could only move by taking a cell the other one never leaves, neither moves.
+    LowerIdWins,
+    /// No worm moves at all if there is any conflict.
+    Block,
+}
+
+/// The most steps [`GameState::undo()`] can go back.
+pub const UNDO_LIMIT: usize = 256;
+
+/// Everything [`GameState::undo()`] restores.
+#[derive(Debug, Clone, PartialEq)]
+struct Snapshot {
+    level: Level,
+    rules: Ruleset,
+}
+
+/// A level and the rules being applied to it.
+///
+/// Two games are equal when their level, base rules, and rules in effect match.
+/// Undo history, the previous level, the listener, the [tick](Self::tick()), and the [seed](Self::seed()) are not compared,
+/// so games reached by different inputs can be equal.
+///
+/// # Determinism
+///
+/// A step depends only on the game, its seed, and the input. Every tie, like the order [`Property::YOU`] worms move in,
+/// is broken by worm id or by cell rather than by hash order, and the only randomness is [`Self::random()`], a pure function of the seed and tick.
+/// So games started the same way with the same seed and given the same inputs give the same [`StepReport`]s, tick for tick,
+/// which is what replays rely on.
+#[derive(Debug, Clone)]
+pub struct GameState {
+    level: Level,
+    base_rules: Ruleset,
+    rules: Ruleset,
+    rule_scans: usize,
+    history: VecDeque<Snapshot>,
+    previous_level: Option<Level>,
+    listener: Listener,
+    allow_reverse: bool,
+    tick: u64,
+    seed: u64,
+}
+
+impl PartialEq for GameState {
+    fn eq(&self, other: &Self) -> bool {
+        self.level == other.level
+            && self.base_rules == other.base_rules
+            && self.rules == other.rules
+    }
+}
+
+impl Eq for GameState {}
+
+impl Hash for GameState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.level.hash(state);
+        self.base_rules.hash(state);
+        self.rules.hash(state);
+    }
+}
+
+impl GameState {
+    /// Base rules added by [`Self::new()`]: `[TEXT, IS, PUSH]`.
+    pub const DEFAULT_RULES: [(Noun, Property); 1] = [(Noun::TEXT, Property::PUSH)];
+
+    /// Construct a game from its starting level and the rules that hold no matter what text the level contains.
+    /// [`Self::DEFAULT_RULES`] are added, so text can be rearranged; use [`Self::set_base_rules()`] to leave them out.
+    pub fn new(level: Level, mut rules: Ruleset) -> Self {
+        for (noun, property) in Self::DEFAULT_RULES {
+            rules.insert(noun, property);
+        }
+        let mut game = Self {
+            level,
+            base_rules: rules.clone(),
+            rules,
+            rule_scans: 0,
+            history: VecDeque::new(),
+            previous_level: None,
+            listener: Listener::default(),
+            allow_reverse: true,
+            tick: 0,
+            seed: 0,
+        };
+        game.rescan_rules();
+        game
+    }
+
+    /// Replace the base rules with exactly `rules`, without adding [`Self::DEFAULT_RULES`], and rebuild the rules from them.
+    /// Like [`Self::set_allow_reverse()`], this is meant for setting up a game; [`Self::undo()`] doesn't bring back the old base rules.
+    /// A copy of another game built with [`Self::new()`] needs this to match it, since the default rules may have been removed from the original.
+    pub fn set_base_rules(&mut self, rules: Ruleset) {
+        self.base_rules = rules;
+        self.rescan_rules();
+    }
+
+    /// Rebuild the rules from the base rules and every grammatical line of text in the level.
+    /// Returns true if the rules changed.
+    fn rescan_rules(&mut self) -> bool {
+        self.rule_scans += 1;
+        let mut rules = self.base_rules.clone();
+        for statement in self.level.statements() {
+            for statement in statement.split().into_iter().flatten() {
+                if let Some((noun, property)) = statement.as_rule() {
+                    rules.insert(noun, property);
+                } else if let Some((from, into)) = statement.as_transformation() {
+                    rules.insert_transformation(from, into);
+                } else if let Some((noun, on, property)) = statement.as_conditional_rule() {
+                    rules.insert_conditional(noun, on, property);
+                }
+            }
+        }
+        let changed = rules != self.rules;
+        self.rules = rules;
+        changed
+    }
+
+    /// How many times the rules have been rebuilt from text.
+    /// Rules are only rebuilt on steps that move text, so this is mostly useful for checking that.
+    pub fn rule_scans(&self) -> usize {
+        self.rule_scans
+    }
+
+    /// How many steps have been taken. [`Self::step()`] and [`Self::step_all_you()`] each count one, even if nothing changed.
+    /// [`Self::undo()`] doesn't wind it back, so it never decreases.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// The seed [`Self::random()`] is derived from. 0 unless set with [`Self::set_seed()`].
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Derive [`Self::random()`] from `seed` from now on, e.g. the seed a replay was recorded with.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// A pseudorandom number for the current tick, for anything that should vary from step to step without breaking replays.
+    /// It depends on nothing but the [seed](Self::seed()) and the [tick](Self::tick()), so it's the same every time they are.
+    pub fn random(&self) -> u64 {
+        // SplitMix64's finalizer, applied twice so nearby seeds and ticks don't give related numbers
+        fn mix(mut z: u64) -> u64 {
+            z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+        mix(self.seed ^ mix(self.tick))
+    }
+
+    /// Forget every step [`Self::undo()`] could revert, e.g. for a copy that shouldn't carry them along.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// The current level.
+    pub fn level(&self) -> &Level {
+        &self.level
+    }
+
+    /// The level as it was before the last [`Self::step()`], for drawing motion between the two.
+    /// [`None`] before the first step and right after [`Self::undo()`].
+    pub fn previous_level(&self) -> Option<&Level> {
+        self.previous_level.as_ref()
+    }
+
+    /// The rules that hold no matter what text the level contains, as given to [`Self::new()`].
+    pub fn base_rules(&self) -> &Ruleset {
+        &self.base_rules
+    }
+
+    /// The rules in effect: the base rules plus any formed by text in the level.
+    pub fn rules(&self) -> &Ruleset {
+        &self.rules
+    }
+
+    /// Whether [`Property::YOU`] worms can back up into their own necks, as [`Worm::crawl()`] allows.
+    /// On by default.
+    pub fn allow_reverse(&self) -> bool {
+        self.allow_reverse
+    }
+
+    /// Allow or forbid 180-degree turns, as in classic snake.
+    /// While forbidden, an input that would [reverse](Worm::is_reversal) a [`Property::YOU`] worm is ignored for that worm,
+    /// without counting as blocked.
+    pub fn set_allow_reverse(&mut self, allow_reverse: bool) {
+        self.allow_reverse = allow_reverse;
+    }
+
+    /// Whether the worm would ignore an input in `direction` because reversing is forbidden.
+    fn refuses_reverse(&self, worm: &Worm, direction: Direction3) -> bool {
+        !self.allow_reverse && worm.is_reversal(direction)
+    }
+
+    /// The level is won?
+    pub fn is_won(&self) -> bool {
+        self.level.is_won(&self.rules)
+    }
+
+    /// Every direction in which at least one [`Property::YOU`] worm could crawl right now,
+    /// pushing whatever is in its way, in the order of [`Direction3::ALL`].
+    /// Empty if no worm is [`Property::YOU`].
+    pub fn legal_moves(&self) -> Vec<Direction3> {
+        let you = self.level.worm_ids_with(Property::YOU, &self.rules);
+        Direction3::ALL
+            .into_iter()
+            .filter(|&direction| you.iter().any(|&id| {
+                // Pushing changes the level, so try it on a copy
+                let mut level = self.level.clone();
+                let mut worm = level.worm(id).expect("YOU worms should be in the level").clone();
+                !self.refuses_reverse(&worm, direction)
+                    && level.try_push(worm.cell_ahead(direction), direction, id, &self.rules).is_some()
+                    && worm.try_crawl_in(direction, &level, &self.rules).is_ok()
+            }))
+            .collect()
+    }
+
+    /// Go back to how things were before the last [`Self::step()`] that changed anything.
+    /// Up to [`UNDO_LIMIT`] steps are remembered.
+    /// The rules in effect are restored with the level, so undoing a push that broke a rule brings the rule back.
+    ///
+    /// Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop_back() {
+            Some(snapshot) => {
+                self.level = snapshot.level;
+                self.rules = snapshot.rules;
+                self.previous_level = None;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Advance the game by one input.
+    ///
+    /// A step runs in fixed phases, each seeing the level as the previous one left it:
+    /// 1. **Move:** each [`Property::YOU`] worm, in id order, pushes any [`Property::PUSH`] worms or text in its way and crawls in `direction`.
+    ///    Then each [`Property::MOVE`] worm crawls on its own, as in [`Level::step_moving()`].
+    /// 2. **Defeat:** worms that are, or touch something that is, [`Property::DEFEAT`] are destroyed, under the rules the step started with.
+    /// 3. **Rescan:** if any text moved, the rules are rebuilt from it.
+    /// 4. **Transform:** objects turn into other objects as in [`Level::apply_transformations()`], under the new rules.
+    ///    If text was created or destroyed, the rules are rebuilt again.
+    /// 5. **Win:** the level is won if a surviving [`Property::YOU`] worm is on a [`Property::WIN`] goal under the new rules.
+    ///
+    /// Nothing but the win check happens if no worm is [`Property::YOU`] or [`Property::MOVE`].
+    ///
+    /// Steps that change anything can be reverted with [`Self::undo()`].
+    pub fn step(&mut self, direction: Direction3) -> StepReport {
+        self.record(|game| game.advance(direction, None))
+    }
+
+    /// Advance the game by one input like [`Self::step()`], but with every [`Property::YOU`] worm moving at once instead of in id order.
+    ///
+    /// Each worm crawls as if the other [`Property::YOU`] worms weren't there, so a worm can follow right behind another.
+    /// Worms that would then share a cell are in conflict, and `policy` decides which of them move.
+    /// Nothing is pushed, since the order of pushes would decide the outcome; anything else in a worm's way blocks it.
+    pub fn step_all_you(&mut self, direction: Direction3, policy: ConflictPolicy) -> StepReport {
+        self.record(|game| game.advance(direction, Some(policy)))
+    }
+
+    /// Call `listener` with each [`GameEvent`] of every step from now on, replacing any previous listener.
+    /// Events come in the order they happen: each worm's pushes before its own move, each [`GameEvent::Eaten`] right before its [`GameEvent::Destroyed`],
+    /// then [`GameEvent::Won`] last.
+    ///
+    /// Clones of the game don't keep the listener.
+    /// It must be [`Send`] and [`Sync`] so the game still is, e.g. to share states between threads while solving.
+    pub fn set_listener(&mut self, listener: impl FnMut(&GameEvent) + Send + Sync + 'static) {
+        self.listener = Listener(Some(Box::new(listener)));
+    }
+
+    /// Run `advance`, remembering the state from before it for [`Self::undo()`] and [`Self::previous_level()`].
+    fn record(&mut self, advance: impl FnOnce(&mut Self) -> StepReport) -> StepReport {
+        let snapshot = Snapshot {
+            level: self.level.clone(),
+            rules: self.rules.clone(),
+        };
+        let report = advance(self);
+        self.tick += 1;
+        self.previous_level = Some(snapshot.level.clone());
+        if snapshot.level != self.level || snapshot.rules != self.rules {
+            if self.history.len() == UNDO_LIMIT {
+                self.history.pop_front();
+            }
+            self.history.push_back(snapshot);
+        }
+        report
+    }
+
+    /// Pass `event` to the listener, if there is one.
+    fn emit(&mut self, event: GameEvent) {
+        if let Some(listener) = &mut self.listener.0 {
+            listener(&event);
+        }
+    }
+
+    fn advance(&mut self, direction: Direction3, policy: Option<ConflictPolicy>) -> StepReport {
+        let mut report = StepReport::default();
+        // Nothing can happen without something to move
+        if [Property::YOU, Property::MOVE].into_iter().any(|property| !self.level.worm_ids_with(property, &self.rules).is_empty()) {
+            let text_moved = match policy {
+                Some(policy) => self.simultaneous_move_phase(direction, policy, &mut report),
+                None => self.move_phase(direction, &mut report),
+            };
+            self.level.step_moving(&self.rules);
+            self.defeat_phase(&mut report);
+            self.rescan_phase(text_moved);
+            self.transform_phase();
+        }
+        self.win_phase(&mut report);
+        report
+    }
+
+    /// Each [`Property::YOU`] worm, in id order, pushes whatever is ahead of it and then crawls in `direction`.
+    /// Later worms see the level as the earlier ones left it.
+    ///
+    /// Returns whether any text moved.
+    fn move_phase(&mut self, direction: Direction3, report: &mut StepReport) -> bool {
+        let mut text_moved = false;
+        for id in self.level.worm_ids_with(Property::YOU, &self.rules) {
+            // Ids stay valid even if an earlier worm was removed this step
+            let Some(target) = self.level.worm(id)
+                .filter(|worm| !self.refuses_reverse(worm, direction))
+                .map(|worm| worm.cell_ahead(direction)) else {
+                continue;
+            };
+            let Some(pushed) = self.level.try_push(target, direction, id, &self.rules) else {
+                report.blocked = Some(direction);
+                continue;
+            };
+            text_moved |= !pushed.texts.is_empty();
+            for pushed_id in pushed.worms {
+                if !report.pushed.contains(&pushed_id) {
+                    report.pushed.push(pushed_id);
+                }
+                self.emit(GameEvent::Pushed { id: pushed_id });
+            }
+
+            let mut worm = self.level.worm(id).expect("pushing should never remove the pusher").clone();
+            match worm.try_crawl_in(direction, &self.level, &self.rules) {
+                Ok(outcome) => {
+                    self.level.set_crawled_worm(id, worm, outcome);
+                    report.moved.push(id);
+                    self.emit(GameEvent::Moved { id });
+                },
+                Err(_) => report.blocked = Some(direction),
+            }
+        }
+        text_moved
+    }
+
+    /// Every [`Property::YOU`] worm crawls in `direction` at once, with conflicts settled by `policy`.
+    /// See [`Self::step_all_you()`].
+    ///
+    /// Returns whether any text moved, which is never.
+    fn simultaneous_move_phase(&mut self, direction: Direction3, policy: ConflictPolicy, report: &mut StepReport) -> bool {
+        let ids = self.level.worm_ids_with(Property::YOU, &self.rules);
+        let mut without_you = self.level.clone();
+        for &id in &ids {
+            without_you.remove_worm(id);
+        }
+        let mut moves: BTreeMap<WormId, Worm> = ids
+            .iter()
+            .filter_map(|&id| {
+                let mut worm = self.level.worm(id)?.clone();
+                if self.refuses_reverse(&worm, direction) {
+                    return None;
+                }
+                worm.try_crawl_in(direction, &without_you, &self.rules).ok().map(|_| (id, worm))
+            })
+            .collect();
+
+        loop {
+            let position = |id: WormId| moves.get(&id).or(self.level.worm(id)).expect("YOU worms should be in the level");
+            // Worms that both stay put started apart, so only pairs with a mover can conflict
+            let conflict = ids
+                .iter()
+                .enumerate()
+                .flat_map(|(i, &lower)| ids[i + 1..].iter().map(move |&higher| (lower, higher)))
+                .find(|&(lower, higher)| (moves.contains_key(&lower) || moves.contains_key(&higher))
+                    && position(lower).overlaps(position(higher))
+                );
+            let Some((lower, higher)) = conflict else {
+                break;
+            };
+            match policy {
+                ConflictPolicy::CancelAll => {
+                    moves.remove(&lower);
+                    moves.remove(&higher);
+                },
+                ConflictPolicy::LowerIdWins => if moves.remove(&higher).is_none() {
+                    moves.remove(&lower);
+                },
+                ConflictPolicy::Block => moves.clear(),
+            }
+        }
+
+        if moves.len() < ids.len() {
+            report.blocked = Some(direction);
+        }
+        report.moved.extend(moves.keys());
+        for &id in moves.keys() {
+            self.emit(GameEvent::Moved { id });
+        }
+        self.level.replace_worms(moves);
+        false
+    }
+
+    /// Worms that are [`Property::DEFEAT`] in any of their cells, or touching something that is, are removed.
+    /// This uses the rules from the start of the step, even if text moved.
+    fn defeat_phase(&mut self, report: &mut StepReport) {
+        let (level, rules) = (&self.level, &self.rules);
+        // Each destroyed worm, with what ate it if it wasn't DEFEAT itself
+        let defeated: Vec<(WormId, Option<Object>)> = level.worms()
+            .filter_map(|(id, worm)| if worm.segment_positions().any(|cell| rules.properties_of_at(Noun::WORM, cell, level).contains(&Property::DEFEAT)) {
+                Some((id, None))
+            } else {
+                worm.segment_positions()
+                    .find_map(|cell| level.touching(id, cell, Property::DEFEAT, rules))
+                    .map(|eater| (id, Some(eater)))
+            })
+            .collect();
+        for (id, eater) in defeated {
+            if let Some(eater) = eater {
+                self.emit(GameEvent::Eaten { eater, eaten: id });
+            }
+            self.level.remove_worm(id);
+            report.destroyed.push(id);
+            self.emit(GameEvent::Destroyed { id });
+        }
+    }
+
+    /// The rules are rebuilt from the text, if any moved.
+    fn rescan_phase(&mut self, text_moved: bool) {
+        // Text only forms lines with other text, so nothing else moving can change the rules
+        if text_moved {
+            self.rescan_rules();
+        }
+    }
+
+    /// Objects turn into other objects under the current rules.
+    /// Transforming text can change the rules, which are then rebuilt, but not applied again until the next step.
+    fn transform_phase(&mut self) {
+        let rules = self.rules.clone();
+        if self.level.apply_transformations(&rules) {
+            self.level.compact();
+        }
+        if rules.transformations().into_iter().any(|(from, into)| from == Noun::TEXT || into == Noun::TEXT) {
+            self.rescan_rules();
+        }
+    }
+
+    /// Whether the level is won is decided last, with the new rules and only the worms that survived.
+    fn win_phase(&mut self, report: &mut StepReport) {
+        report.won = self.is_won();
+        if report.won {
+            self.emit(GameEvent::Won);
+        }
+    }
+}
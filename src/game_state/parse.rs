@@ -0,0 +1,361 @@
+//! Text format for a [`GameState`]: everything a designer needs to author a puzzle in one file.
+//!
+//! The file starts with the line `bounds <x> <y> <z>` giving the [`Level::bounds()`], followed by sections that each hold one kind of object.
+//! A section starts with its name in brackets on its own line, e.g. `[walls]`, and runs until the next section.
+//! Sections may come in any order, be repeated, or be left out.
+//! Blank lines and anything after a `#` are ignored.
+//!
+//! | Section     | Each line                              | Meaning                                                         |
+//! |-------------|----------------------------------------|-----------------------------------------------------------------|
+//! | `[walls]`   | `<x> <y> <z>`                          | [`Level::add_wall()`]                                           |
+//! | `[walls <axis>=<n>]` | A row of `X` and `.`          | Walls in the plane whose [`Axis3i`] coordinate is `n`, drawn as a grid, e.g. `[walls z=0]` for the floor |
+//! | `[goals]`   | `<x> <y> <z>`                          | [`Level::add_goal()`]                                           |
+//! | `[portals]` | `<x> <y> <z> <x> <y> <z>`              | [`Level::link_portal()`] from the first cell to the second      |
+//! | `[text]`    | `<x> <y> <z> <word>`                   | [`Level::add_text()`] showing the word                          |
+//! | `[worms]`   | `<x> <y> <z> [segments]`               | [`Level::add_worm()`] with the head at the cell                 |
+//! | `[rules]`   | `<words>`                              | Base rules, given to [`GameState::new()`]. The words must [split](crate::rules::statement::Statement::split()) into `NOUN IS PROPERTY`, `NOUN IS NOUN`, or `NOUN ON NOUN IS PROPERTY` rules. |
+//!
+//! Each object line is read like the [`crate::level::parse`] line for the same object, without its keyword.
+//!
+//! A slice section holds one row per cell along the plane's second axis, with one `X` (wall) or `.` (no wall) per cell along its first.
+//! The rows count down to 0, so with the origin in the bottom left, `[walls z=0]` looks like the floor seen from above.
+//! Walls are written back out a floor at a time in `[walls z=<n>]` sections.
+//!
+//! The [default rules](GameState::DEFAULT_RULES), like `TEXT IS PUSH`, hold unless taken away with e.g. `TEXT IS NOT PUSH`, so they never need to be written.
+//!
+//! Example:
+//! ```text
+//! bounds 6 1 1
+//!
+//! [walls z=0]
+//! . . . . . X
+//!
+//! [goals]
+//! 4 0 0
+//!
+//! [worms]
+//! 1 0 0 <
+//!
+//! [rules]
+//! WORM IS YOU
+//! GOAL IS WIN
+//! ```
+
+use std::{fmt, str::FromStr};
+use crate::{
+    spacial::{axis3i::Axis3i, direction3::Direction3, vector3i::Vector3i},
+    level::{Level, parse::LevelParseError},
+    rules::{ruleset::Ruleset, statement::Statement, word::{Word, noun::Noun, property::Property}},
+};
+use super::GameState;
+
+/// A part of the file started by a `[name]` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Walls,
+    Goals,
+    Portals,
+    Text,
+    Worms,
+    Rules,
+}
+
+impl Section {
+    /// Every section, in the order they're written.
+    const ALL: [Section; 6] = [
+        Self::Walls,
+        Self::Goals,
+        Self::Portals,
+        Self::Text,
+        Self::Worms,
+        Self::Rules,
+    ];
+
+    /// The name between the brackets.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Walls => "walls",
+            Self::Goals => "goals",
+            Self::Portals => "portals",
+            Self::Text => "text",
+            Self::Worms => "worms",
+            Self::Rules => "rules",
+        }
+    }
+
+    /// The [`crate::level::parse`] keyword for the objects in the section, or [`None`] for the rules.
+    fn keyword(self) -> Option<&'static str> {
+        match self {
+            Self::Walls => Some("wall"),
+            Self::Goals => Some("goal"),
+            Self::Portals => Some("portal"),
+            Self::Text => Some("text"),
+            Self::Worms => Some("worm"),
+            Self::Rules => None,
+        }
+    }
+}
+
+/// A `[walls <axis>=<n>]` section: the plane of cells whose coordinate along `axis` is `n`, drawn as a grid.
+///
+/// The plane's other two axes, in `x`, `y`, `z` order, run across each row and then up the rows,
+/// so the first row is the far edge of the level and the last row is at 0, as in a map with the origin in the bottom left.
+/// For `z=0`, each row is a line of x and the rows count down in y.
+/// Each cell is `X` for a wall or `.` for no wall, and whitespace between cells is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Slice {
+    /// 1-based line number of the header.
+    line: usize,
+    axis: Axis3i,
+    n: i32,
+    /// Each row and its 1-based line number, in the order they're written.
+    rows: Vec<(usize, String)>,
+}
+
+impl Slice {
+    /// The axes across each row and up the rows of the plane across `axis`.
+    fn plane_axes(axis: Axis3i) -> (Axis3i, Axis3i) {
+        let mut others = Axis3i::ALL.into_iter().filter(|&other| other != axis);
+        (others.next().expect("three axes"), others.next().expect("three axes"))
+    }
+
+    /// The cell in row `row` counting up from 0, column `column` counting across from 0.
+    fn cell(axis: Axis3i, n: i32, column: i32, row: i32) -> Vector3i {
+        let (across, up) = Self::plane_axes(axis);
+        let unit = |axis| Vector3i::from(Direction3::from_axis_and_sign(axis, 1).expect("positive sign"));
+        unit(axis) * n + unit(across) * column + unit(up) * row
+    }
+
+    /// Add a wall to `level` for every `X`.
+    fn fill(&self, level: &mut Level) -> Result<(), LevelParseError> {
+        let (across, up) = Self::plane_axes(self.axis);
+        let bounds = level.bounds();
+        let (width, height) = (bounds.component(across), bounds.component(up));
+        let syntax = |line, message| LevelParseError::Syntax { line, message };
+        if !(0..bounds.component(self.axis)).contains(&self.n) {
+            return Err(syntax(self.line, format!("{}={} is outside the level", self.axis, self.n)));
+        }
+        if self.rows.len() != height as usize {
+            return Err(syntax(self.line, format!("expected {height} rows, found {}", self.rows.len())));
+        }
+        for ((line, row), y) in self.rows.iter().zip((0..height).rev()) {
+            let cells: Vec<char> = row.chars().filter(|c| !c.is_whitespace()).collect();
+            if cells.len() != width as usize {
+                return Err(syntax(*line, format!("expected {width} cells, found {}", cells.len())));
+            }
+            for (x, c) in (0..).zip(cells) {
+                match c {
+                    'X' => level.add_wall(Self::cell(self.axis, self.n, x, y)),
+                    '.' => {},
+                    _ => return Err(syntax(*line, format!("invalid cell '{c}': expected 'X' or '.'"))),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The rows for the plane through `level` at `n` along `axis`, in the order they're written.
+    fn rows_of(level: &Level, axis: Axis3i, n: i32) -> impl '_ + Iterator<Item = String> {
+        let (across, up) = Self::plane_axes(axis);
+        let bounds = level.bounds();
+        (0..bounds.component(up)).rev().map(move |y| (0..bounds.component(across))
+            .map(|x| if level.is_solid(Self::cell(axis, n, x, y)) { "X" } else { "." })
+            .collect::<Vec<_>>()
+            .join(" ")
+        )
+    }
+}
+
+/// A section and the `<axis>=<n>` plane in its header, if there is one.
+type Header = (Section, Option<(Axis3i, i32)>);
+
+/// Read the section named by a `[name]` or `[name <axis>=<n>]` line,
+/// or [`None`] if the line isn't a section header.
+fn parse_section(line: &str) -> Option<Result<Header, String>> {
+    let header = line.strip_prefix('[')?;
+    let parse = || {
+        let header = header
+            .strip_suffix(']')
+            .ok_or_else(|| format!("unclosed section header '{line}'"))?;
+        let mut args = header.split_whitespace();
+        let name = args.next().unwrap_or("");
+        let section = Section::ALL
+            .into_iter()
+            .find(|section| section.name() == name)
+            .ok_or_else(|| format!("unknown section '{name}'"))?;
+        let plane = args.next().map(parse_plane).transpose()?;
+        if args.next().is_some() {
+            return Err(format!("too many arguments for section '{name}'"));
+        }
+        if plane.is_some() && section != Section::Walls {
+            return Err(format!("only walls can be drawn as a slice, not '{name}'"));
+        }
+        Ok((section, plane))
+    };
+    Some(parse())
+}
+
+/// Read the `<axis>=<n>` naming a plane, e.g. `z=0`.
+fn parse_plane(arg: &str) -> Result<(Axis3i, i32), String> {
+    let (axis, n) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("invalid plane '{arg}': expected <axis>=<n>"))?;
+    Ok((
+        axis.parse::<Axis3i>().map_err(|e| e.to_string())?,
+        n.parse().map_err(|e| format!("invalid coordinate '{n}': {e}"))?,
+    ))
+}
+
+/// Read a line of the `[rules]` section into `rules`, or into `removed` for default rules it takes away.
+fn parse_rule<'a>(args: impl Iterator<Item = &'a str>, rules: &mut Ruleset, removed: &mut Vec<(Noun, Property)>) -> Result<(), String> {
+    let statement = args
+        .map(|arg| arg.parse::<Word>().map_err(|e| e.to_string()))
+        .collect::<Result<Statement, _>>()?;
+    for rule in statement.split().map_err(|e| e.to_string())? {
+        if let Some((noun, property)) = rule.as_rule() {
+            rules.insert(noun, property);
+        } else if let Some((from, into)) = rule.as_transformation() {
+            rules.insert_transformation(from, into);
+        } else if let Some((noun, on, property)) = rule.as_conditional_rule() {
+            rules.insert_conditional(noun, on, property);
+        } else if let Some(rule) = rule.as_negated_rule().filter(|rule| GameState::DEFAULT_RULES.contains(rule)) {
+            removed.push(rule);
+        } else {
+            return Err(format!("'{rule}' is not a base rule"));
+        }
+    }
+    Ok(())
+}
+
+impl FromStr for GameState {
+    type Err = LevelParseError;
+
+    /// Objects are handed to [`Level::from_str()`] as its own keyword lines, so the level is checked the same way.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Ruleset::new();
+        let mut removed = Vec::new();
+        let mut slices: Vec<Slice> = Vec::new();
+        let mut section = None;
+        let mut level_text = String::with_capacity(s.len());
+        for (index, line) in s.lines().enumerate() {
+            let syntax = |message: String| LevelParseError::Syntax { line: index + 1, message };
+            let content = line
+                .split_once('#')
+                .map_or(line, |(content, _comment)| content)
+                .trim();
+            if let Some(header) = parse_section(content) {
+                let (header, plane) = header.map_err(syntax)?;
+                if let Some((axis, n)) = plane {
+                    slices.push(Slice { line: index + 1, axis, n, rows: Vec::new() });
+                }
+                section = Some((header, plane.is_some()));
+            } else if !content.is_empty() {
+                match section {
+                    Some((Section::Walls, true)) => slices
+                        .last_mut()
+                        .expect("a slice section starts a slice")
+                        .rows
+                        .push((index + 1, content.to_string())),
+                    Some((Section::Rules, _)) => parse_rule(content.split_whitespace(), &mut rules, &mut removed).map_err(syntax)?,
+                    Some((section, _)) => {
+                        level_text.push_str(section.keyword().expect("only the rules have no keyword"));
+                        level_text.push(' ');
+                        level_text.push_str(content);
+                    },
+                    None if content.split_whitespace().next() == Some("bounds") => level_text.push_str(content),
+                    None => return Err(syntax(format!("expected bounds or a section, found '{content}'"))),
+                }
+            }
+            // Keep every line so the level's errors are reported on the right lines
+            level_text.push('\n');
+        }
+        let mut level: Level = level_text.parse()?;
+        if !slices.is_empty() {
+            for slice in &slices {
+                slice.fill(&mut level)?;
+            }
+            level.validate().map_err(LevelParseError::Invalid)?;
+        }
+        let mut game = GameState::new(level, rules);
+        if !removed.is_empty() {
+            let mut rules = game.base_rules().clone();
+            for (noun, property) in removed {
+                rules.remove(noun, property);
+            }
+            game.set_base_rules(rules);
+        }
+        Ok(game)
+    }
+}
+
+/// Write the section's header and lines, or nothing if there are no lines.
+fn write_section(f: &mut fmt::Formatter<'_>, section: Section, lines: impl IntoIterator<Item = String>) -> fmt::Result {
+    let mut lines = lines.into_iter().peekable();
+    if lines.peek().is_some() {
+        writeln!(f, "\n[{}]", section.name())?;
+        for line in lines {
+            writeln!(f, "{line}")?;
+        }
+    }
+    Ok(())
+}
+
+/// The cells sorted so the same level always produces the same text.
+fn sorted<T>(mut items: Vec<(Vector3i, T)>) -> impl Iterator<Item = (Vector3i, T)> {
+    items.sort_by_key(|(cell, _)| (cell.x, cell.y, cell.z));
+    items.into_iter()
+}
+
+/// Writes the current level and the base rules in the format read by [`GameState::from_str()`].
+/// Empty sections are left out, and cells are sorted so the same game always produces the same text.
+/// The undo history is not written.
+impl fmt::Display for GameState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = &self.level;
+        let Vector3i { x, y, z } = level.bounds();
+        writeln!(f, "bounds {x} {y} {z}")?;
+        // Drawn a floor at a time, with any walls outside the level listed on their own
+        let (inside, outside): (Vec<_>, Vec<_>) = level.walls().partition(|&cell| level.in_bounds(cell));
+        let mut floors: Vec<i32> = inside.into_iter().map(|cell| cell.z).collect();
+        floors.sort();
+        floors.dedup();
+        for z in floors {
+            writeln!(f, "\n[{} {}={z}]", Section::Walls.name(), Axis3i::Z)?;
+            for row in Slice::rows_of(level, Axis3i::Z, z) {
+                writeln!(f, "{row}")?;
+            }
+        }
+        write_section(f, Section::Walls, sorted(outside.into_iter().map(|cell| (cell, ())).collect())
+            .map(|(Vector3i { x, y, z }, ())| format!("{x} {y} {z}"))
+        )?;
+        write_section(f, Section::Goals, sorted(level.goals().map(|cell| (cell, ())).collect())
+            .map(|(Vector3i { x, y, z }, ())| format!("{x} {y} {z}"))
+        )?;
+        write_section(f, Section::Portals, sorted(level.portals().collect())
+            .map(|(Vector3i { x, y, z }, exit)| format!("{x} {y} {z} {} {} {}", exit.x, exit.y, exit.z))
+        )?;
+        write_section(f, Section::Text, sorted(level.texts().collect())
+            .map(|(Vector3i { x, y, z }, text)| format!("{x} {y} {z} {}", text.word()))
+        )?;
+        // Worms keep their id order, so they're given the same ids when read back
+        write_section(f, Section::Worms, level.worms().map(|(_, worm)| {
+            let Vector3i { x, y, z } = worm.head_position();
+            format!("{x} {y} {z} {}", worm.to_segments_string())
+        }))?;
+        let rules = &self.base_rules;
+        write_section(f, Section::Rules, rules
+            .properties()
+            .into_iter()
+            // Added back by `GameState::new()`, so only written when they've been taken away
+            .filter(|rule| !GameState::DEFAULT_RULES.contains(rule))
+            .map(|(noun, property)| format!("{noun} IS {property}"))
+            .chain(GameState::DEFAULT_RULES
+                .into_iter()
+                .filter(|&(noun, property)| !rules.has(noun, property))
+                .map(|(noun, property)| format!("{noun} IS NOT {property}"))
+            )
+            .chain(rules.transformations().into_iter().map(|(from, into)| format!("{from} IS {into}")))
+            .chain(rules.conditionals().into_iter().map(|(noun, on, property)| format!("{noun} ON {on} IS {property}")))
+        )
+    }
+}
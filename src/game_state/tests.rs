@@ -0,0 +1,885 @@
+use super::*;
+use crate::{spacial::vector3i::Vector3i, worm::Worm};
+
+fn corridor() -> Level {
+    let mut level = Level::new(Vector3i::new(6, 1, 1));
+    level.add_wall(Vector3i::new(5, 0, 0));
+    level
+}
+
+mod step {
+    use super::*;
+
+    #[test]
+    fn test_moved() {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        let report = game.step(Direction3::East);
+        assert_eq!(report.moved, [worm], "YOU worm should move");
+        assert_eq!(report.blocked, None);
+        assert_eq!(game.level().worm(worm).unwrap().head_position(), Vector3i::new(2, 0, 0));
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(2, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        let report = game.step(Direction3::West);
+        assert_eq!(report.moved, [worm], "worm should be able to back up into its own neck");
+        assert_eq!(game.level().worm(worm).unwrap().head_position(), Vector3i::new(1, 0, 0));
+    }
+
+    #[test]
+    fn test_not_you() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::new());
+        let report = game.step(Direction3::East);
+        assert_eq!(report, StepReport::default(), "nothing should happen without YOU");
+    }
+
+    #[test]
+    fn test_blocked() {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(4, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        let report = game.step(Direction3::East);
+        assert!(report.moved.is_empty(), "worm should not move into wall");
+        assert_eq!(report.blocked, Some(Direction3::East));
+        assert_eq!(game.level().worm(worm).unwrap().head_position(), Vector3i::new(4, 0, 0));
+    }
+
+    #[test]
+    fn test_pushed() {
+        let mut level = corridor();
+        let first = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let second = level.add_worm(Worm::new(Vector3i::new(2, 0, 0), []));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::WORM, Property::PUSH),
+        ]));
+        let report = game.step(Direction3::East);
+        assert_eq!(report.pushed, [second], "worm in the way should be pushed");
+        assert_eq!(report.moved, [first, second]);
+        assert_eq!(game.level().worm(first).unwrap().head_position(), Vector3i::new(2, 0, 0));
+        assert_eq!(game.level().worm(second).unwrap().head_position(), Vector3i::new(4, 0, 0));
+    }
+
+    #[test]
+    fn test_push_blocked() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(3, 0, 0), [Direction3::West]));
+        level.add_worm(Worm::new(Vector3i::new(4, 0, 0), []));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::WORM, Property::PUSH),
+        ]));
+        let report = game.step(Direction3::East);
+        assert!(report.pushed.is_empty(), "worm against a wall cannot be pushed");
+        assert_eq!(report.blocked, Some(Direction3::East));
+    }
+
+    #[test]
+    fn test_destroyed() {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        level.add_goal(Vector3i::new(2, 0, 0));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::GOAL, Property::DEFEAT),
+        ]));
+        let report = game.step(Direction3::East);
+        assert_eq!(report.destroyed, [worm], "worm touching DEFEAT should be destroyed");
+        assert_eq!(game.level().worm_count(), 0, "destroyed worm should be removed");
+    }
+
+    #[test]
+    fn test_destroyed_keeps_other_ids() {
+        let mut level = Level::new(Vector3i::new(6, 2, 1));
+        let doomed = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let survivor = level.add_worm(Worm::new(Vector3i::new(1, 1, 0), [Direction3::West]));
+        level.add_goal(Vector3i::new(2, 0, 0));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::GOAL, Property::DEFEAT),
+        ]));
+        let report = game.step(Direction3::East);
+        assert_eq!(report.moved, [doomed, survivor]);
+        assert_eq!(report.destroyed, [doomed]);
+        assert!(game.level().worm(doomed).is_none());
+        assert_eq!(
+            game.level().worm(survivor).map(Worm::head_position),
+            Some(Vector3i::new(2, 1, 0)),
+            "removing the first worm should not change which worm the second id refers to",
+        );
+    }
+
+    #[test]
+    fn test_won() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        level.add_goal(Vector3i::new(2, 0, 0));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::GOAL, Property::WIN),
+        ]));
+        assert!(!game.is_won());
+        let report = game.step(Direction3::East);
+        assert!(report.won, "reaching the goal should win");
+    }
+}
+
+mod text {
+    use super::*;
+    use crate::rules::{text::Text, word::Word, statement::Statement};
+
+    #[test]
+    fn test_push_forms_rule() {
+        let mut level = Level::new(Vector3i::new(6, 4, 1));
+        level.add_text(Vector3i::new(0, 2, 0), Text::new("WORM".parse().unwrap()));
+        level.add_text(Vector3i::new(1, 2, 0), Text::new("IS".parse().unwrap()));
+        level.add_text(Vector3i::new(3, 2, 0), Text::new("WIN".parse().unwrap()));
+        let worm = level.add_worm(Worm::new(Vector3i::new(4, 2, 0), [Direction3::East]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        assert!(game.level().statements().is_empty());
+        assert!(!game.rules().has(Noun::WORM, Property::WIN));
+
+        let report = game.step(Direction3::West);
+        assert_eq!(report.moved, [worm], "text should be shoved out of the way");
+        assert_eq!(game.level().text_at(Vector3i::new(2, 2, 0)).map(|text| text.word()), Some(Word::Property(Property::WIN)));
+        assert_eq!(game.level().text_at(Vector3i::new(3, 2, 0)), None);
+        assert_eq!(game.level().statements(), ["WORM IS WIN".split_whitespace().map(|word| word.parse().unwrap()).collect::<Statement>()]);
+        assert!(game.rules().has(Noun::WORM, Property::WIN), "the new line should be rescanned into a rule");
+    }
+
+    #[test]
+    fn test_without_default_push() {
+        let mut level = corridor();
+        level.add_text(Vector3i::new(2, 0, 0), Text::new(Word::Property(Property::WIN)));
+        let worm = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::YOU)]);
+        let mut game = GameState::new(level, rules.clone());
+        assert!(game.base_rules().has(Noun::TEXT, Property::PUSH), "added by default");
+        game.set_base_rules(rules);
+        assert!(!game.rules().has(Noun::TEXT, Property::PUSH));
+        assert!(game.step(Direction3::East).moved.is_empty(), "text that isn't PUSH should block");
+        assert_eq!(game.level().worm(worm).unwrap().head_position(), Vector3i::new(1, 0, 0));
+    }
+
+    #[test]
+    fn test_push_breaks_rule() {
+        let mut level = Level::new(Vector3i::new(6, 4, 1));
+        level.add_text(Vector3i::new(0, 2, 0), Text::new("WORM".parse().unwrap()));
+        level.add_text(Vector3i::new(1, 2, 0), Text::new("IS".parse().unwrap()));
+        level.add_text(Vector3i::new(2, 2, 0), Text::new("YOU".parse().unwrap()));
+        level.add_worm(Worm::new(Vector3i::new(2, 1, 0), [Direction3::South]));
+        let mut game = GameState::new(level, Ruleset::new());
+        assert!(game.rules().has(Noun::WORM, Property::YOU), "text should form rules from the start");
+
+        game.step(Direction3::North);
+        assert_eq!(game.level().text_at(Vector3i::new(2, 3, 0)).map(|text| text.word()), Some(Word::Property(Property::YOU)));
+        assert!(!game.rules().has(Noun::WORM, Property::YOU), "broken line should no longer apply");
+        assert!(game.step(Direction3::North).moved.is_empty(), "worm should no longer be controllable");
+    }
+
+    #[test]
+    fn test_rescan_only_when_text_moves() {
+        let mut level = Level::new(Vector3i::new(6, 4, 1));
+        level.add_text(Vector3i::new(0, 3, 0), Text::new("WORM".parse().unwrap()));
+        level.add_text(Vector3i::new(1, 3, 0), Text::new("IS".parse().unwrap()));
+        level.add_text(Vector3i::new(2, 3, 0), Text::new("YOU".parse().unwrap()));
+        level.add_text(Vector3i::new(3, 1, 0), Text::new("WIN".parse().unwrap()));
+        level.add_worm(Worm::new(Vector3i::new(1, 1, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::new());
+        let scans = game.rule_scans();
+
+        game.step(Direction3::East);
+        assert_eq!(game.rule_scans(), scans, "moving without touching text should reuse the rules");
+        game.step(Direction3::East);
+        assert_eq!(game.rule_scans(), scans + 1, "pushing text should rescan");
+        assert!(game.rules().has(Noun::WORM, Property::YOU));
+    }
+
+    #[test]
+    fn test_transformation() {
+        let mut level = Level::new(Vector3i::new(6, 4, 1));
+        level.add_text(Vector3i::new(0, 3, 0), Text::new("WALL".parse().unwrap()));
+        level.add_text(Vector3i::new(1, 3, 0), Text::new("IS".parse().unwrap()));
+        level.add_text(Vector3i::new(2, 3, 0), Text::new("WORM".parse().unwrap()));
+        level.add_wall(Vector3i::new(5, 0, 0));
+        level.add_worm(Worm::new(Vector3i::new(1, 1, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        assert!(game.rules().transforms(Noun::WALL, Noun::WORM));
+
+        game.step(Direction3::East);
+        assert_eq!(game.level().walls().count(), 0);
+        assert_eq!(game.level().worm_count(), 2, "the wall should become a worm");
+        assert!(game.level().worms().any(|(_, worm)| *worm == Worm::new(Vector3i::new(5, 0, 0), [])));
+    }
+}
+
+mod undo {
+    use super::*;
+
+    fn game() -> GameState {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]))
+    }
+
+    #[test]
+    fn test_start() {
+        assert!(!game().undo(), "nothing to undo at the start");
+    }
+
+    #[test]
+    fn test_step() {
+        let mut game = game();
+        let before = game.level().clone();
+        game.step(Direction3::East);
+        assert_ne!(game.level(), &before);
+        assert!(game.undo());
+        assert_eq!(game.level(), &before, "undo should restore the exact prior level");
+        assert!(!game.undo(), "only one step was taken");
+    }
+
+    #[test]
+    fn test_blocked_step() {
+        let mut game = game();
+        game.step(Direction3::East);
+        let before = game.level().clone();
+        game.step(Direction3::South);
+        assert_eq!(game.level(), &before, "out of bounds move should do nothing");
+        assert!(game.undo());
+        assert_ne!(game.level(), &before, "undo should skip steps that changed nothing");
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut game = game();
+        for _ in 0..UNDO_LIMIT + 10 {
+            game.step(Direction3::East);
+            game.step(Direction3::West);
+        }
+        let mut undone = 0;
+        while game.undo() {
+            undone += 1;
+        }
+        assert_eq!(undone, UNDO_LIMIT);
+    }
+
+    #[test]
+    fn test_clears_previous_level() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        game.step(Direction3::East);
+        assert!(game.undo());
+        assert_eq!(game.previous_level(), None, "undo should not leave a trail");
+    }
+
+    #[test]
+    fn test_restores_rules() {
+        use crate::rules::text::Text;
+
+        let mut level = Level::new(Vector3i::new(6, 4, 1));
+        level.add_text(Vector3i::new(0, 2, 0), Text::new("WORM".parse().unwrap()));
+        level.add_text(Vector3i::new(1, 2, 0), Text::new("IS".parse().unwrap()));
+        level.add_text(Vector3i::new(2, 2, 0), Text::new("YOU".parse().unwrap()));
+        let worm = level.add_worm(Worm::new(Vector3i::new(2, 1, 0), [Direction3::South]));
+        let mut game = GameState::new(level, Ruleset::new());
+
+        game.step(Direction3::North);
+        assert!(!game.rules().has(Noun::WORM, Property::YOU), "pushing YOU away should break the rule");
+        assert!(game.undo());
+        assert!(game.rules().has(Noun::WORM, Property::YOU), "undo should restore the rule");
+        assert_eq!(game.level().worm(worm).unwrap().head_position(), Vector3i::new(2, 1, 0));
+        assert_eq!(game.step(Direction3::North).moved, [worm], "worm should be controllable again");
+    }
+}
+
+mod float {
+    use super::*;
+
+    #[test]
+    fn test_passes_over_wall() {
+        let mut level = corridor();
+        level.add_wall(Vector3i::new(3, 0, 0));
+        let worm = level.add_worm(Worm::new(Vector3i::new(2, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::WORM, Property::FLOAT),
+        ]));
+        let report = game.step(Direction3::East);
+        assert_eq!(report.moved, [worm], "floating worm should not be blocked by a grounded wall");
+        assert_eq!(game.level().worm(worm).unwrap().head_position(), Vector3i::new(3, 0, 0));
+    }
+
+    #[test]
+    fn test_floating_wall_blocks() {
+        let mut level = corridor();
+        level.add_wall(Vector3i::new(3, 0, 0));
+        level.add_worm(Worm::new(Vector3i::new(2, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::WORM, Property::FLOAT),
+            (Noun::WALL, Property::FLOAT),
+        ]));
+        assert!(game.step(Direction3::East).moved.is_empty(), "things on the same layer should still collide");
+    }
+
+    #[test]
+    fn test_passes_over_text() {
+        use crate::rules::text::Text;
+        let mut level = corridor();
+        level.add_text(Vector3i::new(3, 0, 0), Text::new("WIN".parse().unwrap()));
+        let worm = level.add_worm(Worm::new(Vector3i::new(2, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::WORM, Property::FLOAT),
+        ]));
+        let report = game.step(Direction3::East);
+        assert_eq!(report.moved, [worm]);
+        assert!(game.level().text_at(Vector3i::new(3, 0, 0)).is_some(), "text should not be pushed by a floating worm");
+    }
+}
+
+mod phases {
+    use super::*;
+    use crate::rules::text::Text;
+
+    #[test]
+    fn test_defeat_before_win() {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        level.add_goal(Vector3i::new(2, 0, 0));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::GOAL, Property::WIN),
+            (Noun::GOAL, Property::DEFEAT),
+        ]));
+        let report = game.step(Direction3::East);
+        assert_eq!(report.destroyed, [worm]);
+        assert!(!report.won, "a worm destroyed on the goal should not win");
+    }
+
+    #[test]
+    fn test_defeat_before_rescan() {
+        let mut level = Level::new(Vector3i::new(6, 4, 1));
+        level.add_text(Vector3i::new(0, 2, 0), Text::new("GOAL".parse().unwrap()));
+        level.add_text(Vector3i::new(1, 2, 0), Text::new("IS".parse().unwrap()));
+        level.add_text(Vector3i::new(2, 2, 0), Text::new("DEFEAT".parse().unwrap()));
+        level.add_goal(Vector3i::new(2, 2, 0));
+        let worm = level.add_worm(Worm::new(Vector3i::new(2, 1, 0), [Direction3::South]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        assert!(game.rules().has(Noun::GOAL, Property::DEFEAT));
+
+        let report = game.step(Direction3::North);
+        assert_eq!(report.moved, [worm], "worm should push DEFEAT off the goal and take its place");
+        assert_eq!(report.destroyed, [worm], "defeat should use the rules from before the text moved");
+        assert!(!game.rules().has(Noun::GOAL, Property::DEFEAT), "rules should still be rescanned afterwards");
+    }
+}
+
+mod previous_level {
+    use super::*;
+
+    #[test]
+    fn test_step() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level.clone(), Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        assert_eq!(game.previous_level(), None);
+        game.step(Direction3::East);
+        assert_eq!(game.previous_level(), Some(&level));
+        let before_blocked = game.level().clone();
+        game.step(Direction3::North);
+        assert_eq!(game.previous_level(), Some(&before_blocked), "even a step that changes nothing should replace the trail");
+    }
+}
+
+mod legal_moves {
+    use super::*;
+
+    #[test]
+    fn test_corridor() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(2, 0, 0), [Direction3::West]));
+        let game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        assert_eq!(game.legal_moves(), [Direction3::East, Direction3::West]);
+    }
+
+    #[test]
+    fn test_dead_end() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(4, 0, 0), [Direction3::West]));
+        let game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        assert_eq!(game.legal_moves(), [Direction3::West], "only backing up is possible against the wall");
+    }
+
+    #[test]
+    fn test_not_you() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(2, 0, 0), [Direction3::West]));
+        let game = GameState::new(level, Ruleset::new());
+        assert!(game.legal_moves().is_empty());
+    }
+}
+
+mod hash {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash_of(game: &GameState) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        game.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_ignores_history() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::YOU)]);
+        let mut game = GameState::new(level.clone(), rules.clone());
+        let fresh = GameState::new(level, rules);
+        game.step(Direction3::East);
+        assert_ne!(hash_of(&game), hash_of(&fresh), "moving should change the hash");
+        game.step(Direction3::West);
+        assert_eq!(game, fresh, "the same position reached by a different route should be equal");
+        assert_eq!(hash_of(&game), hash_of(&fresh));
+    }
+}
+
+mod format_report {
+    use super::*;
+
+    #[test]
+    fn test_full() {
+        let mut level = corridor();
+        let first = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let second = level.add_worm(Worm::new(Vector3i::new(2, 0, 0), []));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU), (Noun::WORM, Property::PUSH)]));
+        let report = game.step(Direction3::East);
+        assert_eq!(report.moved, [first, second]);
+        assert_eq!(format_report(&report), "moved: 0, 1\ndestroyed: -\npushed: 1\nwon: false\nblocked: -");
+    }
+
+    #[test]
+    fn test_blocked() {
+        let report = StepReport { blocked: Some(Direction3::North), won: true, ..Default::default() };
+        assert_eq!(format_report(&report), "moved: -\ndestroyed: -\npushed: -\nwon: true\nblocked: North");
+    }
+}
+
+mod step_all_you {
+    use super::*;
+
+    /// Racing east, `a` runs into the middle of `b` while `b` only moves its head away.
+    /// `c` is nowhere near either.
+    fn race(b_first: bool) -> (GameState, WormId, WormId, WormId) {
+        let mut level = Level::new(Vector3i::new(8, 4, 1));
+        let a_worm = Worm::from_str(Vector3i::new(1, 1, 0), "<").unwrap();
+        let b_worm = Worm::from_str(Vector3i::new(2, 2, 0), "vv").unwrap();
+        let (b, a) = if b_first {
+            (level.add_worm(b_worm), level.add_worm(a_worm))
+        } else {
+            let a = level.add_worm(a_worm);
+            (level.add_worm(b_worm), a)
+        };
+        let c = level.add_worm(Worm::from_str(Vector3i::new(5, 3, 0), "<").unwrap());
+        (GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)])), a, b, c)
+    }
+
+    #[test]
+    fn test_cancel_all() {
+        let (mut game, _, _, c) = race(true);
+        let report = game.step_all_you(Direction3::East, ConflictPolicy::CancelAll);
+        assert_eq!(report.moved, [c], "both racers should stop");
+        assert_eq!(report.blocked, Some(Direction3::East));
+    }
+
+    #[test]
+    fn test_lower_id_wins() {
+        let (mut game, _, b, c) = race(true);
+        let report = game.step_all_you(Direction3::East, ConflictPolicy::LowerIdWins);
+        assert_eq!(report.moved, [b, c], "the lower id should get through");
+        assert_eq!(game.level().worm(b).unwrap().head_position(), Vector3i::new(3, 2, 0));
+        assert!(game.level().validate().is_ok());
+    }
+
+    #[test]
+    fn test_lower_id_cannot_take_cell() {
+        let (mut game, a, _, c) = race(false);
+        let report = game.step_all_you(Direction3::East, ConflictPolicy::LowerIdWins);
+        assert!(!report.moved.contains(&a), "the loser never leaves the cell the winner wants");
+        assert_eq!(report.moved, [c]);
+    }
+
+    #[test]
+    fn test_block() {
+        let (mut game, _, _, _) = race(true);
+        let before = game.level().clone();
+        let report = game.step_all_you(Direction3::East, ConflictPolicy::Block);
+        assert!(report.moved.is_empty(), "nobody should move");
+        assert_eq!(game.level(), &before);
+    }
+
+    #[test]
+    fn test_follow() {
+        let mut level = Level::new(Vector3i::new(8, 1, 1));
+        let front = level.add_worm(Worm::from_str(Vector3i::new(3, 0, 0), "<").unwrap());
+        let back = level.add_worm(Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap());
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        let report = game.step_all_you(Direction3::East, ConflictPolicy::Block);
+        assert_eq!(report.moved, [front, back], "a worm can follow into the cell another is leaving");
+    }
+}
+
+mod parse {
+    use super::*;
+    use crate::level::parse::LevelParseError;
+
+    #[test]
+    fn test_rules() {
+        let game: GameState = "bounds 6 1 1\n[rules]\nWORM IS YOU AND PUSH\nWALL IS WORM\n[worms]\n1 0 0 <".parse().unwrap();
+        assert!(game.base_rules().has(Noun::WORM, Property::YOU));
+        assert!(game.base_rules().has(Noun::WORM, Property::PUSH));
+        assert!(game.base_rules().transforms(Noun::WALL, Noun::WORM));
+        assert_eq!(game.level().worm_count(), 1);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU), (Noun::GOAL, Property::WIN)]));
+        let text = game.to_string();
+        assert!(text.ends_with("\n[rules]\nWORM IS YOU\nGOAL IS WIN\n"), "TEXT IS PUSH is implied:\n{text}");
+        assert_eq!(text.parse::<GameState>(), Ok(game));
+    }
+
+    #[test]
+    fn test_without_default() {
+        let game: GameState = "bounds 6 1 1\n[rules]\nWORM IS YOU\nTEXT IS NOT PUSH".parse().unwrap();
+        assert!(!game.base_rules().has(Noun::TEXT, Property::PUSH));
+        let text = game.to_string();
+        assert!(text.ends_with("\n[rules]\nWORM IS YOU\nTEXT IS NOT PUSH\n"), "{text}");
+        assert_eq!(text.parse::<GameState>(), Ok(game));
+    }
+
+    #[test]
+    fn test_errors() {
+        let result = "bounds 6 1 1\n[rules]\nWORM IS NOT YOU".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 3, .. })), "negated rules can't be base rules");
+        let result = "bounds 6 1 1\n[rules]\nWORM IS\n[walls]\n1 0 0".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 3, .. })));
+        let result = "bounds 6 1 1\n[rules]\nWORM IS YOU\n\n[walls]\n1 0".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 6, .. })), "level errors should keep their line numbers");
+        let result = "bounds 6 1 1\nwall 1 0 0".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 2, .. })), "objects belong in a section");
+        let result = "bounds 6 1 1\n[wall]\n1 0 0".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 2, .. })), "unknown section");
+        let result = "[walls]\n1 0 0\nbounds 6 1 1".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 2, .. })), "bounds must come first");
+    }
+
+    #[test]
+    fn test_slices() {
+        let game: GameState = "bounds 3 2 2\n[walls z=0]\nXXX\nX X X\n[walls x=2]\nX X\n. .\n[worms]\n0 1 1".parse().unwrap();
+        let mut walls: Vec<_> = game.level().walls().collect();
+        walls.sort_by_key(|cell| (cell.z, cell.y, cell.x));
+        assert_eq!(walls, [
+            Vector3i::new(0, 0, 0), Vector3i::new(1, 0, 0), Vector3i::new(2, 0, 0),
+            Vector3i::new(0, 1, 0), Vector3i::new(1, 1, 0), Vector3i::new(2, 1, 0),
+            Vector3i::new(2, 0, 1),
+            Vector3i::new(2, 1, 1),
+        ]);
+        let text = game.to_string();
+        assert!(text.contains("[walls z=0]\nX X X\nX X X\n\n[walls z=1]\n. . X\n. . X\n"), "walls should be written a floor at a time:\n{text}");
+        assert_eq!(text.parse::<GameState>(), Ok(game));
+    }
+
+    #[test]
+    fn test_slice_rows() {
+        let game: GameState = "bounds 3 2 1\n[walls z=0]\nX . .\n. . X".parse().unwrap();
+        assert!(game.level().is_solid(Vector3i::new(0, 1, 0)), "the first row is the far edge");
+        assert!(game.level().is_solid(Vector3i::new(2, 0, 0)), "the last row is at 0");
+        assert_eq!(game.level().walls().count(), 2);
+    }
+
+    #[test]
+    fn test_slice_errors() {
+        let result = "bounds 6 1 1\n[walls w=0]\n......".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 2, .. })), "w is not an axis");
+        let result = "bounds 6 1 1\n[walls z0]\n......".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 2, .. })));
+        let result = "bounds 6 1 1\n[goals z=0]\n......".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 2, .. })), "only walls can be a slice");
+        let result = "bounds 6 1 1\n[walls z=1]\n......".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 2, .. })), "the plane must be in the level");
+        let result = "bounds 6 1 1\n[walls z=0]".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 2, .. })), "too few rows");
+        let result = "bounds 6 1 1\n[walls z=0]\n.....".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 3, .. })), "too few cells");
+        let result = "bounds 6 1 1\n[walls z=0]\n.....o".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 3, .. })), "unknown cell");
+        let result = "bounds 6 1 1\n[worms]\n1 0 0\n[walls x=1]\nX".parse::<GameState>();
+        assert!(matches!(result, Err(LevelParseError::Invalid(_))), "a wall can't be drawn through a worm");
+    }
+}
+
+mod moving {
+    use super::*;
+
+    #[test]
+    fn test_moves_without_you() {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::MOVE)]));
+        let report = game.step(Direction3::West);
+        assert!(report.moved.is_empty(), "MOVE worms aren't controlled by input");
+        assert_eq!(game.level().worm(worm).unwrap().head_position(), Vector3i::new(2, 0, 0));
+    }
+}
+
+mod listener {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_events() {
+        let mut level = corridor();
+        let first = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let second = level.add_worm(Worm::new(Vector3i::new(2, 0, 0), []));
+        level.add_goal(Vector3i::new(3, 0, 0));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::WORM, Property::PUSH),
+            (Noun::GOAL, Property::WIN),
+        ]));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        game.set_listener({
+            let events = events.clone();
+            move |&event| events.lock().unwrap().push(event)
+        });
+
+        game.step(Direction3::East);
+        game.step(Direction3::East);
+        assert_eq!(*events.lock().unwrap(), [
+            GameEvent::Pushed { id: second },
+            GameEvent::Moved { id: first },
+            GameEvent::Moved { id: second },
+            GameEvent::Moved { id: first },
+            GameEvent::Won,
+        ], "events should come in the order they happen, not grouped by kind");
+    }
+
+    #[test]
+    fn test_eaten() {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        level.add_goal(Vector3i::new(2, 0, 0));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::GOAL, Property::DEFEAT),
+        ]));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        game.set_listener({
+            let events = events.clone();
+            move |&event| events.lock().unwrap().push(event)
+        });
+
+        game.step(Direction3::East);
+        assert_eq!(*events.lock().unwrap(), [
+            GameEvent::Moved { id: worm },
+            GameEvent::Eaten { eater: Object::Goal, eaten: worm },
+            GameEvent::Destroyed { id: worm },
+        ]);
+    }
+
+    #[test]
+    fn test_defeat_not_eaten() {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::WORM, Property::DEFEAT),
+        ]));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        game.set_listener({
+            let events = events.clone();
+            move |&event| events.lock().unwrap().push(event)
+        });
+
+        game.step(Direction3::East);
+        assert_eq!(*events.lock().unwrap(), [GameEvent::Moved { id: worm }, GameEvent::Destroyed { id: worm }]);
+    }
+
+    #[test]
+    fn test_move_worm_silent() {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::MOVE)]));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        game.set_listener({
+            let events = events.clone();
+            move |&event| events.lock().unwrap().push(event)
+        });
+        game.step(Direction3::West);
+        assert_eq!(game.level().worm(worm).unwrap().head_position(), Vector3i::new(2, 0, 0));
+        assert!(events.lock().unwrap().is_empty(), "a MOVE worm crawling on its own should fire no events");
+    }
+
+    #[test]
+    fn test_not_cloned() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        game.set_listener({
+            let events = events.clone();
+            move |&event| events.lock().unwrap().push(event)
+        });
+        game.clone().step(Direction3::East);
+        assert!(events.lock().unwrap().is_empty(), "a clone should not call the original's listener");
+    }
+}
+
+mod allow_reverse {
+    use super::*;
+
+    fn game() -> (GameState, WormId) {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(2, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        game.set_allow_reverse(false);
+        (game, worm)
+    }
+
+    #[test]
+    fn test_reverse_ignored() {
+        let (mut game, _) = game();
+        let before = game.level().clone();
+        assert_eq!(game.step(Direction3::West), StepReport::default(), "reversing should be a no-op");
+        assert_eq!(game.level(), &before);
+        assert!(!game.legal_moves().contains(&Direction3::West));
+    }
+
+    #[test]
+    fn test_forward_allowed() {
+        let (mut game, worm) = game();
+        assert_eq!(game.step(Direction3::East).moved, [worm]);
+    }
+
+    #[test]
+    fn test_tailless() {
+        let mut level = corridor();
+        let worm = level.add_worm(Worm::new(Vector3i::new(2, 0, 0), []));
+        let mut game = GameState::new(level, Ruleset::from_iter([(Noun::WORM, Property::YOU)]));
+        game.set_allow_reverse(false);
+        game.step(Direction3::East);
+        assert_eq!(game.step(Direction3::West).moved, [worm], "a tailless worm has nothing to reverse into");
+    }
+}
+
+mod conditional {
+    use super::*;
+
+    #[test]
+    fn test_you_on_goal() {
+        let mut game: GameState = "bounds 6 1 1\n[goals]\n1 0 0\n[worms]\n1 0 0\n[rules]\nWORM ON GOAL IS YOU".parse().unwrap();
+        let worm = game.level().worms().next().unwrap().0;
+        assert_eq!(game.step(Direction3::East).moved, [worm], "the worm starts on the goal");
+        assert!(game.step(Direction3::East).moved.is_empty(), "off the goal, the worm is no longer YOU");
+        assert!(game.to_string().ends_with("[rules]\nWORM ON GOAL IS YOU\n"));
+    }
+
+    #[test]
+    fn test_win_on_goal() {
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        level.add_goal(Vector3i::new(3, 0, 0));
+        let mut rules = Ruleset::from_iter([(Noun::WORM, Property::YOU)]);
+        rules.insert_conditional(Noun::WORM, Noun::GOAL, Property::WIN);
+        let mut game = GameState::new(level, rules);
+        assert!(!game.step(Direction3::East).won, "the worm is only WIN on the goal");
+        assert!(game.step(Direction3::East).won);
+    }
+
+    #[test]
+    fn test_you_on_goal_wins() {
+        let mut rules = Ruleset::from_iter([(Noun::GOAL, Property::WIN)]);
+        rules.insert_conditional(Noun::WORM, Noun::GOAL, Property::YOU);
+        let mut level = corridor();
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        level.add_goal(Vector3i::new(3, 0, 0));
+        assert!(!GameState::new(level.clone(), rules.clone()).is_won(), "the worm isn't YOU off the goal");
+        level.add_goal(Vector3i::new(1, 0, 0));
+        let mut game = GameState::new(level, rules);
+        assert!(game.is_won(), "the head is YOU on a goal that is WIN");
+        assert!(game.step(Direction3::East).won, "the tail is still on the goal");
+    }
+}
+
+mod tick {
+    use super::*;
+
+    const LEVEL: &str = "bounds 8 4 1\n[rules]\nWORM IS YOU AND PUSH\nWALL IS MOVE\n[walls]\n7 0 0\n[goals]\n6 3 0\n[text]\n3 1 0 WALL\n4 1 0 IS\n5 1 0 WORM\n[worms]\n1 1 0 <\n1 3 0 v";
+
+    #[test]
+    fn test_counts_steps() {
+        let mut game: GameState = LEVEL.parse().unwrap();
+        assert_eq!(game.tick(), 0);
+        game.step(Direction3::East);
+        game.step_all_you(Direction3::North, ConflictPolicy::Block);
+        assert_eq!(game.tick(), 2);
+        game.undo();
+        assert_eq!(game.tick(), 2, "undo shouldn't wind the tick back");
+    }
+
+    #[test]
+    fn test_same_seed() {
+        use Direction3::*;
+        let inputs = [East, East, North, East, South, South, West, East, East, North, North, East];
+        let mut first: GameState = LEVEL.parse().unwrap();
+        let mut second: GameState = LEVEL.parse().unwrap();
+        first.set_seed(42);
+        second.set_seed(42);
+        for direction in inputs {
+            let tick = first.tick();
+            assert_eq!(first.random(), second.random(), "tick {tick}");
+            assert_eq!(first.step(direction), second.step(direction), "tick {tick}");
+            assert_eq!(first, second, "tick {tick}");
+            assert_eq!(first.tick(), second.tick());
+        }
+    }
+
+    #[test]
+    fn test_random_varies() {
+        let mut game: GameState = LEVEL.parse().unwrap();
+        let mut reseeded = game.clone();
+        reseeded.set_seed(1);
+        assert_ne!(game.random(), reseeded.random(), "different seeds should give different numbers");
+        let before = game.random();
+        game.step(Direction3::East);
+        assert_ne!(game.random(), before, "each tick should give a different number");
+        game.undo();
+        assert_ne!(game.random(), before, "undo doesn't wind the tick back, so it shouldn't repeat a number either");
+    }
+}
+
+mod threads {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_send_sync() {
+        assert_send_sync::<Worm>();
+        assert_send_sync::<Level>();
+        assert_send_sync::<GameState>();
+    }
+}
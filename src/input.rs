@@ -0,0 +1,45 @@
+//! Buffering of directional input between game ticks.
+//!
+//! The game only steps on tick boundaries, so a press that lands just before a tick would otherwise be dropped.
+//! An [`InputBuffer`] holds on to the most recent press for [`BUFFER_WINDOW`] seconds and hands it to the next tick.
+//! Times are plain seconds from any monotonic clock (e.g. raylib's `get_time()`), so the buffer can be driven by a fake clock in tests.
+
+use crate::spacial::direction3::Direction3;
+
+#[cfg(test)]
+mod tests;
+
+/// Seconds between game steps while a direction is being fed in.
+pub const TICK_INTERVAL: f64 = 0.125;
+
+/// How long, in seconds, a press stays buffered before it is considered stale: 100 ms, a little under a [`TICK_INTERVAL`].
+/// A press late in a tick carries over to the next one, but a press made early in a tick, while the last step is still animating,
+/// is dropped rather than acted on so late that the input feels sticky.
+pub const BUFFER_WINDOW: f64 = 0.8 * TICK_INTERVAL;
+
+/// Remembers the most recent directional press until it is consumed or expires.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct InputBuffer {
+    pending: Option<(Direction3, f64)>,
+}
+
+impl InputBuffer {
+    /// Construct an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a press of `direction` at time `now`, replacing any earlier press.
+    pub fn press(&mut self, direction: Direction3, now: f64) {
+        self.pending = Some((direction, now));
+    }
+
+    /// Consume the buffered press, if one was made within [`BUFFER_WINDOW`] of `now`.
+    /// The buffer is empty afterwards either way.
+    pub fn take(&mut self, now: f64) -> Option<Direction3> {
+        self.pending
+            .take()
+            .filter(|&(_, pressed_at)| now - pressed_at <= BUFFER_WINDOW)
+            .map(|(direction, _)| direction)
+    }
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+mod take {
+    use super::*;
+
+    #[test]
+    fn test_within_window() {
+        let mut buffer = InputBuffer::new();
+        buffer.press(Direction3::East, 1.0);
+        assert_eq!(buffer.take(1.05), Some(Direction3::East));
+        assert_eq!(buffer.take(1.05), None, "a press should only be consumed once");
+    }
+
+    #[test]
+    fn test_just_before_tick() {
+        let mut buffer = InputBuffer::new();
+        let tick = 1.0;
+        buffer.press(Direction3::East, tick + TICK_INTERVAL - 0.05);
+        // The next tick may only be noticed a frame late
+        assert_eq!(buffer.take(tick + TICK_INTERVAL + 1.0 / 60.0), Some(Direction3::East), "the press should carry over to the next tick");
+    }
+
+    #[test]
+    fn test_just_past_window() {
+        let mut buffer = InputBuffer::new();
+        buffer.press(Direction3::East, 1.0);
+        assert_eq!(buffer.take(1.0 + BUFFER_WINDOW + 0.001), None, "a press just past the window should be dropped");
+    }
+
+    #[test]
+    fn test_expired() {
+        let mut buffer = InputBuffer::new();
+        buffer.press(Direction3::East, 1.0);
+        assert_eq!(buffer.take(1.0 + BUFFER_WINDOW * 2.0), None);
+    }
+
+    #[test]
+    fn test_most_recent() {
+        let mut buffer = InputBuffer::new();
+        buffer.press(Direction3::East, 1.0);
+        buffer.press(Direction3::North, 1.02);
+        assert_eq!(buffer.take(1.05), Some(Direction3::North), "the later press should win");
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(InputBuffer::new().take(0.0), None);
+    }
+}
@@ -0,0 +1,785 @@
+//! The puzzle space.
+
+use std::{
+    collections::{HashSet, HashMap, BTreeMap, VecDeque, hash_map::DefaultHasher},
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Deref, DerefMut},
+};
+use crate::{
+    spacial::{vector3i::Vector3i, direction3::Direction3, axis3i::Axis3i},
+    rules::{ruleset::Ruleset, statement::Statement, text::Text, word::{Word, noun::Noun, property::Property}},
+    worm::{Worm, CrawlOutcome},
+};
+
+pub mod validate;
+pub mod parse;
+pub mod delta;
+mod occupancy;
+use occupancy::Occupancy;
+
+#[cfg(test)]
+mod tests;
+
+/// Identifies a worm within a [`Level`].
+/// Unlike a position in a list, a worm's id never changes while it is in the level, even as other worms are removed.
+/// Ids are handed out in increasing order and are never reused by the same level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WormId(u32);
+
+/// One thing in a cell of a [`Level`], as listed by [`Level::objects_at()`].
+/// A cell can hold several at once, e.g. a worm over a goal; collisions and pushes look at each in turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Object {
+    /// A wall.
+    Wall,
+    /// A goal.
+    Goal,
+    /// A text block.
+    Text(Text),
+    /// A segment of the worm.
+    Worm(WormId),
+}
+
+impl Object {
+    /// The noun rules use to refer to the object.
+    pub fn noun(self) -> Noun {
+        match self {
+            Self::Wall => Noun::WALL,
+            Self::Goal => Noun::GOAL,
+            Self::Text(_) => Noun::TEXT,
+            Self::Worm(_) => Noun::WORM,
+        }
+    }
+}
+
+/// The reason [`Level::spawn_worm()`] could not place a worm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnError {
+    /// Part of the worm would be outside [`Level::bounds()`].
+    OutOfBounds {
+        /// The first of its cells found out of bounds.
+        cell: Vector3i,
+    },
+    /// The worm crosses over itself.
+    SelfIntersecting {
+        /// The first cell found occupied twice.
+        cell: Vector3i,
+    },
+    /// Part of the worm would be inside a wall.
+    InWall {
+        /// The first of its cells found in a wall.
+        cell: Vector3i,
+    },
+    /// Part of the worm would share a cell with a worm already in the level.
+    Overlaps {
+        /// The worm already there.
+        other: WormId,
+        /// The first shared cell found.
+        cell: Vector3i,
+    },
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { cell } => write!(f, "the worm would be out of bounds at {cell}"),
+            Self::SelfIntersecting { cell } => write!(f, "the worm crosses itself at {cell}"),
+            Self::InWall { cell } => write!(f, "the worm would be inside a wall at {cell}"),
+            Self::Overlaps { other, cell } => write!(f, "the worm would overlap worm {other} at {cell}"),
+        }
+    }
+}
+
+impl std::error::Error for SpawnError {}
+
+/// The puzzle space worms crawl around in.
+/// Two levels are equal when their bounds, walls, goals, portals, text, and worms (with their ids) all match.
+///
+/// Walls, goals, and text are stored by cell along with an entry for each worm segment, so everything in a cell is found in one lookup.
+/// Worms themselves are stored by id, and every change to a worm made through the level updates its entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level {
+    bounds: Vector3i,
+    portals: HashMap<Vector3i, Vector3i>,
+    cells: Occupancy,
+    worms: BTreeMap<WormId, Worm>,
+    next_worm_id: u32,
+}
+
+/// A worm borrowed mutably from a [`Level`] by [`Level::worm_mut()`].
+/// Dropping it updates which cells the level sees the worm in, so change a worm through this rather than a copy.
+pub struct WormMut<'a> {
+    id: WormId,
+    worm: &'a mut Worm,
+    cells: &'a mut Occupancy,
+    before: HashSet<Vector3i>,
+}
+
+impl Deref for WormMut<'_> {
+    type Target = Worm;
+
+    fn deref(&self) -> &Self::Target {
+        self.worm
+    }
+}
+
+impl DerefMut for WormMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.worm
+    }
+}
+
+impl Drop for WormMut<'_> {
+    fn drop(&mut self) {
+        self.cells.move_worm(self.id, &self.before, self.worm.occupied_cells());
+    }
+}
+
+/// Just the number, e.g. `3`.
+impl fmt::Display for WormId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Hashes a collection whose iteration order is arbitrary, such as a [`HashSet`], so that equal collections hash the same.
+/// Each item is hashed on its own and the results are combined with XOR, which doesn't care about order.
+fn hash_unordered<H: Hasher>(items: impl IntoIterator<Item = impl Hash>, state: &mut H) {
+    let mut combined = 0;
+    let mut len = 0usize;
+    for item in items {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        combined ^= hasher.finish();
+        len += 1;
+    }
+    len.hash(state);
+    combined.hash(state);
+}
+
+/// Consistent with the derived [`PartialEq`]: every field is hashed, and the hash-based collections without regard to order.
+impl Hash for Level {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bounds.hash(state);
+        hash_unordered(&self.portals, state);
+        self.cells.hash(state);
+        self.worms.hash(state);
+        self.next_worm_id.hash(state);
+    }
+}
+
+/// Everything that moved together in a single [`Level::try_push()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pushed {
+    /// The pushed worms.
+    pub worms: Vec<WormId>,
+    /// Cells the pushed text blocks were in before moving.
+    pub texts: Vec<Vector3i>,
+}
+
+impl Pushed {
+    /// Nothing was pushed?
+    pub fn is_empty(&self) -> bool {
+        self.worms.is_empty() && self.texts.is_empty()
+    }
+}
+
+impl Level {
+    /// Construct an empty level spanning from the origin up to (but not including) `bounds`.
+    pub fn new(bounds: Vector3i) -> Self {
+        Self {
+            bounds,
+            portals: HashMap::new(),
+            cells: Occupancy::default(),
+            worms: BTreeMap::new(),
+            next_worm_id: 0,
+        }
+    }
+
+    /// The size of the level.
+    /// In-bounds cells range from the origin up to (but not including) this.
+    pub fn bounds(&self) -> Vector3i {
+        self.bounds
+    }
+
+    /// The cell is inside [`Self::bounds()`]?
+    pub fn in_bounds(&self, cell: Vector3i) -> bool {
+        (0..self.bounds.x).contains(&cell.x)
+            && (0..self.bounds.y).contains(&cell.y)
+            && (0..self.bounds.z).contains(&cell.z)
+    }
+
+    /// The number of cells for which [`Self::in_bounds()`] is true.
+    pub fn cell_count_in_bounds(&self) -> usize {
+        if self.bounds.x <= 0 || self.bounds.y <= 0 || self.bounds.z <= 0 {
+            return 0;
+        }
+        self.bounds.volume() as usize
+    }
+
+    /// The smallest box containing every worm and wall, as its inclusive `(min, max)` corners,
+    /// or [`None`] if the level has neither.
+    pub fn bounding_box(&self) -> Option<(Vector3i, Vector3i)> {
+        self.worms
+            .values()
+            .map(Worm::bounding_box)
+            .chain(self.walls().map(|cell| (cell, cell)))
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.component_min(min_b), max_a.component_max(max_b)))
+    }
+
+    /// Fill a cell with wall.
+    pub fn add_wall(&mut self, cell: Vector3i) {
+        self.cells.insert(cell, Object::Wall);
+    }
+
+    /// Mark a cell as a goal.
+    pub fn add_goal(&mut self, cell: Vector3i) {
+        self.cells.insert(cell, Object::Goal);
+    }
+
+    /// Link the portal at `entrance` to `exit`.
+    /// Portals are one-way links; a usable pair needs a second link from `exit` back to `entrance`.
+    pub fn link_portal(&mut self, entrance: Vector3i, exit: Vector3i) {
+        self.portals.insert(entrance, exit);
+    }
+
+    /// Place a text block in a cell, replacing any already there.
+    /// The block's [position](Text::position()) is set to the cell.
+    pub fn add_text(&mut self, cell: Vector3i, text: Text) {
+        self.cells.insert(cell, Object::Text(text.moved_to(cell)));
+    }
+
+    /// Place a worm in the level.
+    /// Returns the id of the new worm, which comes after every existing worm in [`Self::worms()`].
+    pub fn add_worm(&mut self, worm: Worm) -> WormId {
+        let id = WormId(self.next_worm_id);
+        self.next_worm_id += 1;
+        self.set_worm(id, worm);
+        id
+    }
+
+    /// Put `worm` in the level as `id`, replacing any worm already under that id.
+    /// Only the cells the worm entered or left are updated.
+    fn set_worm(&mut self, id: WormId, worm: Worm) {
+        match self.worms.insert(id, worm) {
+            Some(old) => self.cells.move_worm(id, old.occupied_cells(), self.worms[&id].occupied_cells()),
+            None => self.cells.add_worm(id, self.worms[&id].occupied_cells()),
+        }
+    }
+
+    /// Place a worm in the level like [`Self::add_worm()`], but only if that leaves the level [valid](Self::validate):
+    /// the worm must be in bounds, not cross itself, and not share a cell with a wall or another worm.
+    /// The level is unchanged if it can't be placed.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::{level::{Level, SpawnError}, spacial::vector3i::Vector3i, worm::Worm};
+    /// let mut level = Level::new(Vector3i::new(4, 1, 1));
+    /// let first = level.spawn_worm(Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap()).unwrap();
+    /// let error = level.spawn_worm(Worm::from_str(Vector3i::new(2, 0, 0), "<").unwrap()).unwrap_err();
+    /// assert_eq!(error, SpawnError::Overlaps { other: first, cell: Vector3i::new(1, 0, 0) });
+    /// ```
+    pub fn spawn_worm(&mut self, worm: Worm) -> Result<WormId, SpawnError> {
+        let mut visited = HashSet::new();
+        for cell in worm.segment_positions() {
+            if !self.in_bounds(cell) {
+                return Err(SpawnError::OutOfBounds { cell });
+            }
+            if !visited.insert(cell) {
+                return Err(SpawnError::SelfIntersecting { cell });
+            }
+            if self.is_solid(cell) {
+                return Err(SpawnError::InWall { cell });
+            }
+            if let Some(other) = self.worm_at(cell) {
+                return Err(SpawnError::Overlaps { other, cell });
+            }
+        }
+        Ok(self.add_worm(worm))
+    }
+
+    /// Take a worm out of the level, or [`None`] if there is no worm with that id.
+    /// Other worms keep their ids.
+    pub fn remove_worm(&mut self, id: WormId) -> Option<Worm> {
+        let worm = self.worms.remove(&id)?;
+        self.cells.remove_worm(id, worm.occupied_cells());
+        Some(worm)
+    }
+
+    /// Release storage left over from objects that have been removed, e.g. after [`Self::apply_transformations()`] empties a whole kind.
+    ///
+    /// Cells that held more objects than they do now are shrunk too, along with the map of cells itself.
+    /// This never changes what is in the level; it only shrinks the memory behind it.
+    /// Shrinking reallocates, so only call this after something was actually removed.
+    pub fn compact(&mut self) {
+        self.portals.shrink_to_fit();
+        self.cells.shrink_to_fit();
+    }
+
+    /// Cut the worm `worm` where it passes through `cell`.
+    /// The head side, including `cell` itself, is destroyed; the tail side survives as a shorter worm under the same id.
+    /// If `cell` was the tail, nothing survives and the worm is removed.
+    ///
+    /// Returns whether the worm exists and passed through `cell`.
+    pub fn sever_at(&mut self, worm: WormId, cell: Vector3i) -> bool {
+        let Some(target) = self.worms.get(&worm) else {
+            return false;
+        };
+        let Some(index) = target.segment_positions().position(|position| position == cell) else {
+            return false;
+        };
+        match target.split_at(index + 1).1 {
+            Some(tail_piece) => self.set_worm(worm, tail_piece),
+            None => _ = self.remove_worm(worm),
+        }
+        true
+    }
+
+    /// Spin the walls and worms in the cube of cells within `radius` steps of `center` (along each axis) about `center`,
+    /// like [`Vector3i::rotate_90_about()`].
+    ///
+    /// Only worms entirely inside the cube are turned; worms that stick out of it are left where they are.
+    /// Turned objects may end up overlapping ones that weren't, or outside [`Self::bounds()`], so [validate](Self::validate) afterwards if that matters.
+    pub fn rotate_region(&mut self, center: Vector3i, radius: i32, axis: Axis3i, quarter_turns: i32) {
+        let in_region = |cell: &Vector3i| {
+            let offset = *cell - center;
+            offset.x.abs() <= radius && offset.y.abs() <= radius && offset.z.abs() <= radius
+        };
+        let inside: Vec<Vector3i> = self.walls().filter(in_region).collect();
+        for &cell in &inside {
+            self.cells.remove(cell, |object| object == Object::Wall);
+        }
+        for cell in inside {
+            self.add_wall(cell.rotate_90_about(center, axis, quarter_turns));
+        }
+        let turned: Vec<(WormId, Worm)> = self.worms()
+            .filter(|(_, worm)| worm.segment_positions().all(|cell| in_region(&cell)))
+            .map(|(id, worm)| {
+                let mut worm = worm.clone();
+                let head = worm.head_position();
+                worm.translate(head.rotate_90_about(center, axis, quarter_turns) - head);
+                worm.rotate(axis, quarter_turns);
+                (id, worm)
+            })
+            .collect();
+        self.replace_worms(turned);
+    }
+
+    /// Turn objects into other objects according to every `[Noun(a), IS, Noun(b)]` in `rules`.
+    ///
+    /// Each object of `a` is removed and a `b` is put in each cell it took up:
+    /// walls and goals fill the cell, a worm becomes one tailless worm per cell,
+    /// and text is written with the word for `a`.
+    /// A noun that is also `[a, IS, a]` keeps its form, and all objects are taken out before any are added,
+    /// so `a` turning into `b` never continues on into whatever `b` turns into.
+    ///
+    /// Returns whether any object was transformed.
+    pub fn apply_transformations(&mut self, rules: &Ruleset) -> bool {
+        let transformations: Vec<(Noun, Noun)> = rules.transformations()
+            .into_iter()
+            .filter(|&(from, into)| from != into && !rules.transforms(from, from))
+            .collect();
+        let mut taken: BTreeMap<Noun, Vec<Vector3i>> = BTreeMap::new();
+        for &(from, _) in &transformations {
+            taken.entry(from).or_insert_with(|| self.take_all(from));
+        }
+        for (from, into) in transformations {
+            for &cell in &taken[&from] {
+                match into {
+                    Noun::WORM => _ = self.add_worm(Worm::new(cell, [])),
+                    Noun::GOAL => self.add_goal(cell),
+                    Noun::WALL => self.add_wall(cell),
+                    Noun::TEXT => self.add_text(cell, Text::new(Word::Noun(from))),
+                }
+            }
+        }
+        taken.values().any(|cells| !cells.is_empty())
+    }
+
+    /// Remove every object of `noun`, returning the cells they took up.
+    /// Worms are taken in id order, head to tail; everything else is ordered by cell so the result doesn't depend on hash order.
+    fn take_all(&mut self, noun: Noun) -> Vec<Vector3i> {
+        let mut cells: Vec<Vector3i> = match noun {
+            Noun::WORM => {
+                self.cells.remove_all(|object| matches!(object, Object::Worm(_)));
+                return std::mem::take(&mut self.worms)
+                    .into_values()
+                    .flat_map(|worm| worm.segment_positions().collect::<Vec<_>>())
+                    .collect();
+            },
+            _ => self.cells.remove_all(|object| object.noun() == noun),
+        };
+        cells.sort_by_key(|cell| (cell.z, -cell.y, cell.x));
+        cells
+    }
+
+    /// Nothing can enter the cell?
+    pub fn is_solid(&self, cell: Vector3i) -> bool {
+        self.objects_at(cell).contains(&Object::Wall)
+    }
+
+    /// The cell is a goal?
+    pub fn is_goal(&self, cell: Vector3i) -> bool {
+        self.objects_at(cell).contains(&Object::Goal)
+    }
+
+    /// Every wall cell, in no particular order.
+    pub fn walls(&self) -> impl '_ + Iterator<Item = Vector3i> {
+        self.cells.iter().filter(|&(_, object)| object == Object::Wall).map(|(cell, _)| cell)
+    }
+
+    /// Every goal cell, in no particular order.
+    pub fn goals(&self) -> impl '_ + Iterator<Item = Vector3i> {
+        self.cells.iter().filter(|&(_, object)| object == Object::Goal).map(|(cell, _)| cell)
+    }
+
+    /// Where the portal at `cell` leads, if there is one.
+    pub fn portal_exit(&self, cell: Vector3i) -> Option<Vector3i> {
+        self.portals.get(&cell).copied()
+    }
+
+    /// Everything in the cell: any wall, goal, and text, then the worms in id order.
+    /// Portals aren't objects and aren't included.
+    pub fn objects_at(&self, cell: Vector3i) -> &[Object] {
+        self.cells.at(cell)
+    }
+
+    /// The text block in the cell, if there is one.
+    pub fn text_at(&self, cell: Vector3i) -> Option<Text> {
+        self.objects_at(cell).iter().find_map(|object| match object {
+            Object::Text(text) => Some(*text),
+            _ => None,
+        })
+    }
+
+    /// Every text block and its cell, in no particular order.
+    pub fn texts(&self) -> impl '_ + Iterator<Item = (Vector3i, Text)> {
+        self.cells.iter().filter_map(|(cell, object)| match object {
+            Object::Text(text) => Some((cell, text)),
+            _ => None,
+        })
+    }
+
+    /// Every line of text, read left to right ([`Direction3::East`]) and top to bottom ([`Direction3::South`]).
+    /// A line is a run of at least three blocks; it may or may not be grammatical.
+    /// Lines are ordered by their first cell, so the result doesn't depend on hash order.
+    pub fn statements(&self) -> Vec<Statement> {
+        let blocks = self.texts().flat_map(|(_, text)| [Direction3::East, Direction3::South].map(|direction| text.facing(direction)));
+        Text::lines(blocks)
+            .into_iter()
+            .filter(|line| line.len() >= 3)
+            .map(|line| line.into_iter().map(|text| text.word()).collect())
+            .collect()
+    }
+
+    /// Every portal link as `(entrance, exit)`, in no particular order.
+    pub fn portals(&self) -> impl '_ + Iterator<Item = (Vector3i, Vector3i)> {
+        self.portals.iter().map(|(&entrance, &exit)| (entrance, exit))
+    }
+
+    /// The worm with the id, if it is still in the level.
+    pub fn worm(&self, id: WormId) -> Option<&Worm> {
+        self.worms.get(&id)
+    }
+
+    /// The worm with the id, mutably, if it is still in the level.
+    /// The level sees the worm in its new cells once the returned guard is dropped.
+    pub fn worm_mut(&mut self, id: WormId) -> Option<WormMut<'_>> {
+        let worm = self.worms.get_mut(&id)?;
+        let before = worm.occupied_cells().clone();
+        Some(WormMut { id, worm, cells: &mut self.cells, before })
+    }
+
+    /// Swap in new versions of worms already in the level, e.g. copies that were moved while checking for conflicts.
+    /// Ids that aren't in the level are ignored.
+    /// Only the cells each worm entered or left are updated.
+    pub fn replace_worms(&mut self, worms: impl IntoIterator<Item = (WormId, Worm)>) {
+        for (id, worm) in worms {
+            if self.worms.contains_key(&id) {
+                self.set_worm(id, worm);
+            }
+        }
+    }
+
+    /// Replace the worm with the id by `worm`, which [`Worm::try_crawl_in()`] just moved with `outcome`.
+    /// Cheaper than [`Self::replace_worms()`], since only the new head and vacated tail cell change.
+    pub fn set_crawled_worm(&mut self, id: WormId, worm: Worm, outcome: CrawlOutcome) {
+        let Some(vacated) = outcome.vacated else {
+            // The worm grew, or its tail was also its new head; compare every cell instead
+            return self.set_worm(id, worm);
+        };
+        self.cells.remove(vacated, |object| object == Object::Worm(id));
+        self.cells.insert(worm.head_position(), Object::Worm(id));
+        self.worms.insert(id, worm);
+    }
+
+    /// Every worm in the level and its id, in the order they were added.
+    pub fn worms(&self) -> impl '_ + Iterator<Item = (WormId, &Worm)> {
+        self.worms.iter().map(|(&id, worm)| (id, worm))
+    }
+
+    /// Call `f` on every worm in the level and its id, mutably, in the order they were added.
+    /// The level sees each worm in its new cells once `f` returns.
+    pub fn for_each_worm_mut(&mut self, mut f: impl FnMut(WormId, &mut Worm)) {
+        for (&id, worm) in &mut self.worms {
+            let before = worm.occupied_cells().clone();
+            f(id, worm);
+            self.cells.move_worm(id, &before, worm.occupied_cells());
+        }
+    }
+
+    /// The number of worms in the level.
+    pub fn worm_count(&self) -> usize {
+        self.worms.len()
+    }
+
+    /// Ids of every worm that has `property` under `rules`, in the order of [`Self::worms()`].
+    /// A worm has a conditional property like `[WORM, ON, GOAL, IS, YOU]` if any of its cells [satisfies](Ruleset::properties_of_at) it.
+    pub fn worm_ids_with(&self, property: Property, rules: &Ruleset) -> Vec<WormId> {
+        if rules.has(Noun::WORM, property) {
+            self.worms.keys().copied().collect()
+        } else if rules.has_conditional(Noun::WORM, property) {
+            self.worms()
+                .filter(|(_, worm)| worm
+                    .segment_positions()
+                    .any(|cell| rules.properties_of_at(Noun::WORM, cell, self).contains(&property))
+                )
+                .map(|(id, _)| id)
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Every [`Property::MOVE`] worm, in id order, crawls one cell the way it's [facing](Worm::facing()).
+    /// A blocked worm turns around with [`Worm::reverse()`] and tries the other way instead;
+    /// if that is blocked too, it stays turned around. Nothing is pushed.
+    ///
+    /// Returns the ids of the worms that moved.
+    pub fn step_moving(&mut self, rules: &Ruleset) -> Vec<WormId> {
+        let mut moved = Vec::new();
+        for id in self.worm_ids_with(Property::MOVE, rules) {
+            let mut worm = self.worms[&id].clone();
+            let crawled = worm.try_crawl_in(worm.facing(), self, rules).or_else(|_| {
+                worm.reverse();
+                worm.try_crawl_in(worm.facing(), self, rules)
+            });
+            match crawled {
+                Ok(outcome) => {
+                    self.set_crawled_worm(id, worm, outcome);
+                    moved.push(id);
+                },
+                // Turning around doesn't change the worm's cells, so none are updated
+                Err(_) => self.set_worm(id, worm),
+            }
+        }
+        moved
+    }
+
+    /// The first cell hit stepping from `from` in `direction`, looking at most `max` steps ahead.
+    /// A cell is hit if it is solid or holds a worm or text; `from` itself is never hit.
+    ///
+    /// Returns [`None`] if nothing is hit within `max` steps or before the ray leaves the level.
+    pub fn raycast(&self, from: Vector3i, direction: Direction3, max: usize) -> Option<Vector3i> {
+        std::iter::successors(Some(from + direction), |&cell| Some(cell + direction))
+            .take(max)
+            .take_while(|&cell| self.in_bounds(cell))
+            .find(|&cell| self.is_solid(cell) || self.text_at(cell).is_some() || self.worm_at(cell).is_some())
+    }
+
+    /// The worm occupying the cell, if any.
+    /// If worms overlap, as in a level that fails [validation](Self::validate), it's the one with the lowest id.
+    pub fn worm_at(&self, cell: Vector3i) -> Option<WormId> {
+        self.objects_at(cell).iter().find_map(|object| match object {
+            Object::Worm(id) => Some(*id),
+            _ => None,
+        })
+    }
+
+    /// Shove whatever [`Property::PUSH`] worm or text is in `cell` one step in `direction`,
+    /// along with everything pushable in its way.
+    /// The worm at `pusher` is never pushed and blocks the chain.
+    ///
+    /// Returns what was pushed (nothing if `cell` is free or part of the pusher itself),
+    /// or [`None`] if the chain is blocked, in which case nothing moves.
+    pub fn try_push(&mut self, cell: Vector3i, direction: Direction3, pusher: WormId, rules: &Ruleset) -> Option<Pushed> {
+        if self.worm_at(cell) == Some(pusher) {
+            return Some(Pushed::default());
+        }
+        let mut chain = Pushed::default();
+        if !self.can_push_into(cell, direction, Noun::WORM, pusher, rules, &mut chain) {
+            return None;
+        }
+        let pushed: Vec<_> = chain.worms
+            .iter()
+            .map(|id| {
+                let mut worm = self.worms[id].clone();
+                worm.translate(direction.into());
+                (*id, worm)
+            })
+            .collect();
+        self.replace_worms(pushed);
+        // Lift every block before setting any down, so none land on one that hasn't moved yet
+        let texts: Vec<_> = chain.texts
+            .iter()
+            .map(|&cell| match self.cells.remove(cell, |object| matches!(object, Object::Text(_))) {
+                Some(Object::Text(text)) => (cell + direction, text),
+                _ => panic!("pushed text should exist"),
+            })
+            .collect();
+        for (cell, text) in texts {
+            self.add_text(cell, text);
+        }
+        Some(chain)
+    }
+
+    /// Collects everything that would be pushed by a `mover` entering `cell` into `chain`.
+    /// Only things that [interact](Ruleset::interacts) with the mover block or get pushed by it,
+    /// and only things that are [`Property::PUSH`] [in `cell`](Ruleset::properties_of_at) get pushed.
+    fn can_push_into(&self, cell: Vector3i, direction: Direction3, mover: Noun, pusher: WormId, rules: &Ruleset, chain: &mut Pushed) -> bool {
+        if !self.in_bounds(cell) {
+            return false;
+        }
+        self.objects_at(cell).iter().all(|&object| match object {
+            _ if !rules.interacts(mover, object.noun()) => true,
+            Object::Goal => true,
+            Object::Wall => false,
+            Object::Worm(id) if id == pusher => false,
+            Object::Worm(id) if chain.worms.contains(&id) => true,
+            Object::Text(_) if chain.texts.contains(&cell) => true,
+            _ if !rules.properties_of_at(object.noun(), cell, self).contains(&Property::PUSH) => false,
+            Object::Text(_) => {
+                chain.texts.push(cell);
+                self.can_push_into(cell + direction, direction, Noun::TEXT, pusher, rules, chain)
+            },
+            Object::Worm(id) => {
+                chain.worms.push(id);
+                self.worms[&id]
+                    .segment_positions()
+                    .all(|segment| self.can_push_into(segment + direction, direction, Noun::WORM, pusher, rules, chain))
+            },
+        })
+    }
+
+    /// Which faces of the cell are exposed, indexed in the order of [`Direction3::ALL`].
+    /// A face is exposed when the neighbor in that direction is not solid.
+    ///
+    /// Example:
+    /// ```no_run
+    /// # use puzzle_game_rs::{level::Level, spacial::{vector3i::Vector3i, direction3::Direction3}};
+    /// # let level = Level::new(Vector3i::new(1, 1, 1));
+    /// # let cell = Vector3i::new(0, 0, 0);
+    /// # fn draw_face(cell: Vector3i, direction: Direction3) {}
+    /// let faces = level.solid_faces(cell);
+    /// for (direction, exposed) in Direction3::ALL.into_iter().zip(faces) {
+    ///     if exposed {
+    ///         draw_face(cell, direction);
+    ///     }
+    /// }
+    /// ```
+    pub fn solid_faces(&self, cell: Vector3i) -> [bool; 6] {
+        Direction3::ALL.map(|direction| !self.is_solid(cell + direction))
+    }
+
+    /// Every wall face that isn't hidden against another wall, as the wall cell and the direction the face points.
+    /// These are the only faces that need to be drawn.
+    pub fn exposed_wall_faces(&self) -> impl '_ + Iterator<Item = (Vector3i, Direction3)> {
+        self.walls().flat_map(|cell| Direction3::ALL
+            .into_iter()
+            .zip(self.solid_faces(cell))
+            .filter(|&(_, exposed)| exposed)
+            .map(move |(direction, _)| (cell, direction))
+        )
+    }
+
+    /// The fewest steps to get from `from` to `to` through in-bounds cells that are not solid,
+    /// or [`None`] if `to` can't be reached.
+    ///
+    /// Worms are ignored, including any that are in the way.
+    pub fn shortest_path(&self, from: Vector3i, to: Vector3i) -> Option<Vec<Direction3>> {
+        let is_open = |cell: Vector3i| self.in_bounds(cell) && !self.is_solid(cell);
+        if !is_open(from) || !is_open(to) {
+            return None;
+        }
+
+        // Each visited cell remembers the step that first reached it
+        let mut came_from = HashMap::<Vector3i, Option<Direction3>>::from([(from, None)]);
+        let mut frontier = VecDeque::from([from]);
+        while let Some(cell) = frontier.pop_front() {
+            if cell == to {
+                let mut path = Vec::new();
+                let mut cell = to;
+                while let Some(step) = came_from[&cell] {
+                    path.push(step);
+                    cell -= step;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for (direction, neighbor) in Direction3::ALL.into_iter().zip(cell.neighbors()) {
+                if is_open(neighbor) && !came_from.contains_key(&neighbor) {
+                    came_from.insert(neighbor, Some(direction));
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+        None
+    }
+
+    /// Every in-bounds cell that is not solid and can be reached from `start` by orthogonal steps through such cells, including `start` itself.
+    /// Empty if `start` is out of bounds or solid.
+    ///
+    /// Like [`Self::shortest_path()`], worms are ignored.
+    pub fn reachable_from(&self, start: Vector3i) -> HashSet<Vector3i> {
+        let is_open = |cell: Vector3i| self.in_bounds(cell) && !self.is_solid(cell);
+        if !is_open(start) {
+            return HashSet::new();
+        }
+        let mut reached = HashSet::from([start]);
+        let mut frontier = vec![start];
+        while let Some(cell) = frontier.pop() {
+            for neighbor in cell.neighbors() {
+                if is_open(neighbor) && reached.insert(neighbor) {
+                    frontier.push(neighbor);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Every in-bounds cell that is not solid can be reached from every other, so no part of the level is walled off?
+    /// A level with no open cells at all counts as connected.
+    pub fn is_fully_connected(&self) -> bool {
+        let mut open = Vector3i::iter_box(Vector3i::new(0, 0, 0), self.bounds - 1)
+            .filter(|&cell| !self.is_solid(cell));
+        match open.next() {
+            Some(start) => self.reachable_from(start).len() == 1 + open.count(),
+            None => true,
+        }
+    }
+
+    /// Some cell of a worm that is [`Property::YOU`] there is also [`Property::WIN`],
+    /// either through the worm itself or something else in the cell it [interacts](Ruleset::interacts) with?
+    /// Properties are looked up per cell with [`Ruleset::properties_of_at()`], so conditional rules count.
+    pub fn is_won(&self, rules: &Ruleset) -> bool {
+        self.worms().any(|(id, worm)| worm.segment_positions().any(|cell| {
+            let properties = rules.properties_of_at(Noun::WORM, cell, self);
+            properties.contains(&Property::YOU)
+                && (properties.contains(&Property::WIN) || self.touches(id, cell, Property::WIN, rules))
+        }))
+    }
+
+    /// Something else in `cell` that the worm with the id [interacts](Ruleset::interacts) with has `property` there?
+    pub fn touches(&self, id: WormId, cell: Vector3i, property: Property, rules: &Ruleset) -> bool {
+        self.touching(id, cell, property, rules).is_some()
+    }
+
+    /// The first object in `cell`, in the order of [`Self::objects_at()`], that makes [`Self::touches()`] true.
+    pub fn touching(&self, id: WormId, cell: Vector3i, property: Property, rules: &Ruleset) -> Option<Object> {
+        self.objects_at(cell).iter().copied().find(|&object| object != Object::Worm(id)
+            && rules.interacts(Noun::WORM, object.noun())
+            && rules.properties_of_at(object.noun(), cell, self).contains(&property)
+        )
+    }
+}
@@ -0,0 +1,107 @@
+//! The difference between two versions of a [`Level`], for sending changes instead of whole levels.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::{spacial::vector3i::Vector3i, rules::text::Text, worm::Worm};
+use super::{Level, Object, WormId};
+
+/// Everything that changed between two versions of a [`Level`], made by [`Level::delta()`] and replayed by [`Level::apply_delta()`].
+///
+/// The bounds and portals are assumed to be the same in both versions, since nothing changes them during play.
+/// The rules aren't stored in the level, but the text they come from is, so rule changes arrive as text changes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LevelDelta {
+    worms: BTreeMap<WormId, Worm>,
+    removed_worms: Vec<WormId>,
+    walls_added: HashSet<Vector3i>,
+    walls_removed: HashSet<Vector3i>,
+    goals_added: HashSet<Vector3i>,
+    goals_removed: HashSet<Vector3i>,
+    texts: HashMap<Vector3i, Option<Text>>,
+    next_worm_id: u32,
+}
+
+impl LevelDelta {
+    /// Nothing changed?
+    pub fn is_empty(&self) -> bool {
+        self.worms.is_empty()
+            && self.removed_worms.is_empty()
+            && self.walls_added.is_empty()
+            && self.walls_removed.is_empty()
+            && self.goals_added.is_empty()
+            && self.goals_removed.is_empty()
+            && self.texts.is_empty()
+    }
+
+    /// Worms that moved, changed shape, or were added, as they are now.
+    pub fn changed_worms(&self) -> impl '_ + Iterator<Item = (WormId, &Worm)> {
+        self.worms.iter().map(|(&id, worm)| (id, worm))
+    }
+
+    /// Worms that were removed, in id order.
+    pub fn removed_worms(&self) -> &[WormId] {
+        &self.removed_worms
+    }
+}
+
+impl Level {
+    /// What changed going from `prev` to `self`, such that `prev.apply_delta(&self.delta(prev))` makes `prev` equal to `self`.
+    pub fn delta(&self, prev: &Level) -> LevelDelta {
+        let mut texts: HashMap<Vector3i, Option<Text>> = prev.texts()
+            .filter(|&(cell, _)| self.text_at(cell).is_none())
+            .map(|(cell, _)| (cell, None))
+            .collect();
+        texts.extend(self.texts()
+            .filter(|&(cell, text)| prev.text_at(cell) != Some(text))
+            .map(|(cell, text)| (cell, Some(text)))
+        );
+        let (walls, prev_walls): (HashSet<_>, HashSet<_>) = (self.walls().collect(), prev.walls().collect());
+        let (goals, prev_goals): (HashSet<_>, HashSet<_>) = (self.goals().collect(), prev.goals().collect());
+        LevelDelta {
+            worms: self.worms
+                .iter()
+                .filter(|&(id, worm)| prev.worms.get(id) != Some(worm))
+                .map(|(&id, worm)| (id, worm.clone()))
+                .collect(),
+            removed_worms: prev.worms
+                .keys()
+                .filter(|id| !self.worms.contains_key(id))
+                .copied()
+                .collect(),
+            walls_added: walls.difference(&prev_walls).copied().collect(),
+            walls_removed: prev_walls.difference(&walls).copied().collect(),
+            goals_added: goals.difference(&prev_goals).copied().collect(),
+            goals_removed: prev_goals.difference(&goals).copied().collect(),
+            texts,
+            next_worm_id: self.next_worm_id,
+        }
+    }
+
+    /// Bring the level up to date with a [`LevelDelta`] made by [`Self::delta()`] against a level equal to this one.
+    pub fn apply_delta(&mut self, delta: &LevelDelta) {
+        for &id in &delta.removed_worms {
+            self.remove_worm(id);
+        }
+        for (&id, worm) in &delta.worms {
+            self.set_worm(id, worm.clone());
+        }
+        for &cell in &delta.walls_removed {
+            self.cells.remove(cell, |object| object == Object::Wall);
+        }
+        for &cell in &delta.walls_added {
+            self.add_wall(cell);
+        }
+        for &cell in &delta.goals_removed {
+            self.cells.remove(cell, |object| object == Object::Goal);
+        }
+        for &cell in &delta.goals_added {
+            self.add_goal(cell);
+        }
+        for (&cell, &text) in &delta.texts {
+            match text {
+                Some(text) => self.add_text(cell, text),
+                None => _ = self.cells.remove(cell, |object| matches!(object, Object::Text(_))),
+            }
+        }
+        self.next_worm_id = delta.next_worm_id;
+    }
+}
@@ -0,0 +1,114 @@
+//! Which objects are in each cell of a [`Level`](super::Level).
+
+use std::{collections::{HashMap, HashSet}, hash::{Hash, Hasher}};
+use smallvec::SmallVec;
+use crate::spacial::vector3i::Vector3i;
+use super::{Object, WormId, hash_unordered};
+
+/// Every object in each cell of a level: its wall, goal, and text, and one entry for each worm with a segment there.
+/// A cell rarely holds more than a couple of objects, so they're kept inline rather than in a separate allocation.
+///
+/// Objects in a cell are kept in a fixed order (wall, goal, text, then worms by id) and empty cells are dropped,
+/// so two occupancies holding the same objects compare equal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct Occupancy(HashMap<Vector3i, SmallVec<[Object; 2]>>);
+
+impl Object {
+    /// Where the object goes among the others in its cell.
+    /// A cell holds at most one object of each rank, so there's one of each kind, except for worms, which are told apart by id.
+    fn rank(self) -> (u8, u32) {
+        match self {
+            Self::Wall => (0, 0),
+            Self::Goal => (1, 0),
+            Self::Text(_) => (2, 0),
+            Self::Worm(id) => (3, id.0),
+        }
+    }
+}
+
+impl Occupancy {
+    /// Everything in the cell, in order.
+    pub fn at(&self, cell: Vector3i) -> &[Object] {
+        self.0.get(&cell).map_or(&[], |objects| objects.as_slice())
+    }
+
+    /// Every object with its cell, in no particular order.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = (Vector3i, Object)> {
+        self.0
+            .iter()
+            .flat_map(|(&cell, objects)| objects.iter().map(move |&object| (cell, object)))
+    }
+
+    /// Put the object in the cell, replacing any of the same rank, e.g. any text already there.
+    pub fn insert(&mut self, cell: Vector3i, object: Object) {
+        let objects = self.0.entry(cell).or_default();
+        match objects.binary_search_by_key(&object.rank(), |other| other.rank()) {
+            Ok(index) => objects[index] = object,
+            Err(index) => objects.insert(index, object),
+        }
+    }
+
+    /// Take the first object in the cell that `matches` out, if there is one.
+    pub fn remove(&mut self, cell: Vector3i, matches: impl Fn(Object) -> bool) -> Option<Object> {
+        let objects = self.0.get_mut(&cell)?;
+        let index = objects.iter().position(|&object| matches(object))?;
+        let object = objects.remove(index);
+        if objects.is_empty() {
+            self.0.remove(&cell);
+        }
+        Some(object)
+    }
+
+    /// Take every object that `matches` out of every cell, returning their cells in no particular order.
+    pub fn remove_all(&mut self, matches: impl Fn(Object) -> bool) -> Vec<Vector3i> {
+        let mut removed = Vec::new();
+        self.0.retain(|&cell, objects| {
+            let before = objects.len();
+            objects.retain(|object| !matches(*object));
+            removed.extend(std::iter::repeat_n(cell, before - objects.len()));
+            !objects.is_empty()
+        });
+        removed
+    }
+
+    /// Record the worm as being in each of `cells`.
+    pub fn add_worm<'a>(&mut self, id: WormId, cells: impl IntoIterator<Item = &'a Vector3i>) {
+        for &cell in cells {
+            self.insert(cell, Object::Worm(id));
+        }
+    }
+
+    /// Forget the worm was in each of `cells`.
+    pub fn remove_worm<'a>(&mut self, id: WormId, cells: impl IntoIterator<Item = &'a Vector3i>) {
+        for &cell in cells {
+            self.remove(cell, |object| object == Object::Worm(id));
+        }
+    }
+
+    /// The worm moved from the cells `from` to the cells `to`; only the cells in one but not the other are touched.
+    pub fn move_worm(&mut self, id: WormId, from: &HashSet<Vector3i>, to: &HashSet<Vector3i>) {
+        self.remove_worm(id, from.difference(to));
+        self.add_worm(id, to.difference(from));
+    }
+
+    /// How many cells there's room for without reallocating.
+    #[cfg(test)]
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    /// Release memory left over from removed objects, moving cells that had more objects than fit inline back inline.
+    pub fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit();
+        for objects in self.0.values_mut() {
+            objects.shrink_to_fit();
+        }
+    }
+}
+
+/// Consistent with the derived [`PartialEq`], without regard to the order of cells.
+impl Hash for Occupancy {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_unordered(&self.0, state);
+    }
+}
@@ -0,0 +1,171 @@
+//! Text format for a [`Level`].
+//!
+//! Each line is a keyword followed by whitespace-separated arguments.
+//! Blank lines and anything after a `#` are ignored.
+//!
+//! | Line                                   | Meaning                                                         |
+//! |----------------------------------------|-----------------------------------------------------------------|
+//! | `bounds <x> <y> <z>`                   | [`Level::bounds()`]. Must come before everything else.          |
+//! | `wall <x> <y> <z>`                     | [`Level::add_wall()`]                                           |
+//! | `goal <x> <y> <z>`                     | [`Level::add_goal()`]                                           |
+//! | `portal <x> <y> <z> <x> <y> <z>`       | [`Level::link_portal()`] from the first cell to the second      |
+//! | `text <x> <y> <z> <word>`              | [`Level::add_text()`] showing the word, as in [`Word::from_str()`] |
+//! | `worm <x> <y> <z> [segments]`          | [`Level::add_worm()`] with the head at the cell, segments as in [`Worm::from_str()`] |
+//!
+//! Example:
+//! ```text
+//! bounds 6 1 1
+//! wall 5 0 0
+//! goal 4 0 0
+//! worm 1 0 0 <
+//! ```
+
+use std::{fmt, str::FromStr};
+use crate::{spacial::vector3i::Vector3i, worm::Worm, rules::{text::Text, word::Word}};
+use super::{Level, validate::LevelError};
+
+/// The reason a [`Level`] could not be read from text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LevelParseError {
+    /// A line could not be understood.
+    Syntax {
+        /// 1-based line number.
+        line: usize,
+        /// What was wrong with it.
+        message: String,
+    },
+    /// The text was understood, but the level it describes failed [`Level::validate()`].
+    Invalid(Vec<LevelError>),
+}
+
+impl fmt::Display for LevelParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { line, message } => write!(f, "line {line}: {message}"),
+            Self::Invalid(errors) => {
+                write!(f, "invalid level")?;
+                for (i, error) in errors.iter().enumerate() {
+                    write!(f, "{}{error}", if i == 0 { ": " } else { "; " })?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl std::error::Error for LevelParseError {}
+
+fn parse_cell<'a>(args: &mut impl Iterator<Item = &'a str>) -> Result<Vector3i, String> {
+    let mut coordinate = || args
+        .next()
+        .ok_or_else(|| "missing coordinate".to_string())
+        .and_then(|arg| arg
+            .parse::<i32>()
+            .map_err(|e| format!("invalid coordinate '{arg}': {e}"))
+        );
+    Ok(Vector3i::new(coordinate()?, coordinate()?, coordinate()?))
+}
+
+impl FromStr for Level {
+    type Err = LevelParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut level: Option<Level> = None;
+
+        for (index, line) in s.lines().enumerate() {
+            let syntax = |message: String| LevelParseError::Syntax { line: index + 1, message };
+            let line = line
+                .split_once('#')
+                .map_or(line, |(content, _comment)| content);
+            let mut args = line.split_whitespace();
+            let Some(keyword) = args.next() else {
+                continue;
+            };
+
+            if keyword == "bounds" {
+                if level.is_some() {
+                    return Err(syntax("bounds given more than once".to_string()));
+                }
+                level = Some(Level::new(parse_cell(&mut args).map_err(syntax)?));
+            } else {
+                let level = level
+                    .as_mut()
+                    .ok_or_else(|| syntax("bounds must be given first".to_string()))?;
+                match keyword {
+                    "wall" => level.add_wall(parse_cell(&mut args).map_err(syntax)?),
+                    "goal" => level.add_goal(parse_cell(&mut args).map_err(syntax)?),
+                    "portal" => {
+                        let entrance = parse_cell(&mut args).map_err(syntax)?;
+                        let exit = parse_cell(&mut args).map_err(syntax)?;
+                        level.link_portal(entrance, exit);
+                    },
+                    "text" => {
+                        let cell = parse_cell(&mut args).map_err(syntax)?;
+                        let word = args
+                            .next()
+                            .ok_or_else(|| syntax("missing word".to_string()))?
+                            .parse::<Word>()
+                            .map_err(|e| syntax(e.to_string()))?;
+                        level.add_text(cell, Text::new(word));
+                    },
+                    "worm" => {
+                        let head_position = parse_cell(&mut args).map_err(syntax)?;
+                        let worm = Worm::from_str(head_position, args.next().unwrap_or(""))
+                            .map_err(|e| syntax(e.to_string()))?;
+                        level.add_worm(worm);
+                    },
+                    _ => return Err(syntax(format!("unknown keyword '{keyword}'"))),
+                }
+            }
+
+            if args.next().is_some() {
+                return Err(syntax(format!("too many arguments for '{keyword}'")));
+            }
+        }
+
+        let level = level.ok_or(LevelParseError::Syntax { line: 0, message: "missing bounds".to_string() })?;
+        level.validate().map_err(LevelParseError::Invalid)?;
+        Ok(level)
+    }
+}
+
+/// Writes the level in the format read by [`Level::from_str()`].
+/// Cells are sorted so the same level always produces the same text.
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sorted = |cells: &mut Vec<Vector3i>| cells.sort_by_key(|cell| (cell.x, cell.y, cell.z));
+        let Vector3i { x, y, z } = self.bounds();
+        writeln!(f, "bounds {x} {y} {z}")?;
+
+        let mut walls: Vec<_> = self.walls().collect();
+        sorted(&mut walls);
+        for Vector3i { x, y, z } in walls {
+            writeln!(f, "wall {x} {y} {z}")?;
+        }
+
+        let mut goals: Vec<_> = self.goals().collect();
+        sorted(&mut goals);
+        for Vector3i { x, y, z } in goals {
+            writeln!(f, "goal {x} {y} {z}")?;
+        }
+
+        let mut entrances: Vec<_> = self.portals().map(|(entrance, _)| entrance).collect();
+        sorted(&mut entrances);
+        for entrance in entrances {
+            let exit = self.portal_exit(entrance).expect("entrance should be linked");
+            writeln!(f, "portal {} {} {} {} {} {}", entrance.x, entrance.y, entrance.z, exit.x, exit.y, exit.z)?;
+        }
+
+        let mut texts: Vec<_> = self.texts().collect();
+        texts.sort_by_key(|(cell, _)| (cell.x, cell.y, cell.z));
+        for (Vector3i { x, y, z }, text) in texts {
+            writeln!(f, "text {x} {y} {z} {}", text.word())?;
+        }
+
+        for (_, worm) in self.worms() {
+            let Vector3i { x, y, z } = worm.head_position();
+            writeln!(f, "worm {x} {y} {z} {}", worm.to_segments_string())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1008 @@
+use super::*;
+
+mod solid_faces {
+    use super::*;
+
+    #[test]
+    fn test_enclosed() {
+        let mut level = Level::new(Vector3i::new(8, 8, 8));
+        let cell = Vector3i::new(0, 0, 0);
+        level.add_wall(cell);
+        for direction in Direction3::ALL {
+            level.add_wall(cell + direction);
+        }
+        assert_eq!(level.solid_faces(cell), [false; 6], "enclosed cell should have no exposed faces");
+    }
+
+    #[test]
+    fn test_surface() {
+        let mut level = Level::new(Vector3i::new(8, 8, 8));
+        // floor of 3x3 walls
+        for x in -1..=1 {
+            for y in -1..=1 {
+                level.add_wall(Vector3i::new(x, y, 0));
+            }
+        }
+        let faces = level.solid_faces(Vector3i::new(1, 0, 0));
+        for (direction, exposed) in Direction3::ALL.into_iter().zip(faces) {
+            let expected = matches!(direction, Direction3::East | Direction3::Up | Direction3::Down);
+            assert_eq!(exposed, expected, "{direction:?} face exposure should be {expected}");
+        }
+    }
+}
+
+mod is_won {
+    use super::*;
+
+    fn win_rules() -> Ruleset {
+        Ruleset::from_iter([
+            (Noun::WORM, Property::YOU),
+            (Noun::GOAL, Property::WIN),
+        ])
+    }
+
+    #[test]
+    fn test_goal_under_head() {
+        let mut level = Level::new(Vector3i::new(8, 8, 8));
+        level.add_worm(Worm::new(Vector3i::new(2, 2, 0), [Direction3::West]));
+        level.add_goal(Vector3i::new(2, 2, 0));
+        assert!(level.is_won(&win_rules()), "YOU worm on goal should win");
+    }
+
+    #[test]
+    fn test_rule_inactive() {
+        let mut level = Level::new(Vector3i::new(8, 8, 8));
+        level.add_worm(Worm::new(Vector3i::new(2, 2, 0), [Direction3::West]));
+        level.add_goal(Vector3i::new(2, 2, 0));
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::YOU)]);
+        assert!(!level.is_won(&rules), "goal should not win without [GOAL, IS, WIN]");
+    }
+
+    #[test]
+    fn test_goal_elsewhere() {
+        let mut level = Level::new(Vector3i::new(8, 8, 8));
+        level.add_worm(Worm::new(Vector3i::new(2, 2, 0), [Direction3::West]));
+        level.add_goal(Vector3i::new(5, 5, 0));
+        assert!(!level.is_won(&win_rules()), "worm away from goal should not win");
+    }
+}
+
+mod in_bounds {
+    use super::*;
+
+    #[test]
+    fn test_edges() {
+        let level = Level::new(Vector3i::new(4, 3, 1));
+        assert!(level.in_bounds(Vector3i::new(0, 0, 0)), "origin should be in bounds");
+        assert!(level.in_bounds(Vector3i::new(3, 2, 0)), "far corner should be in bounds");
+        assert!(!level.in_bounds(Vector3i::new(4, 0, 0)), "bounds are exclusive");
+        assert!(!level.in_bounds(Vector3i::new(0, -1, 0)), "negative cells are out of bounds");
+        assert!(!level.in_bounds(Vector3i::new(0, 0, 1)), "flat level has one layer");
+    }
+
+    #[test]
+    fn test_cell_count() {
+        assert_eq!(Level::new(Vector3i::new(8, 8, 1)).cell_count_in_bounds(), 64);
+        assert_eq!(Level::new(Vector3i::new(3, 4, 5)).cell_count_in_bounds(), 60);
+        assert_eq!(Level::new(Vector3i::new(-3, 4, 5)).cell_count_in_bounds(), 0, "negative bounds contain nothing");
+    }
+}
+
+mod validate {
+    use super::*;
+    use crate::level::validate::LevelError;
+
+    #[test]
+    fn test_ok() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        level.add_wall(Vector3i::new(4, 4, 0));
+        level.add_worm(Worm::new(Vector3i::new(1, 1, 0), [Direction3::East, Direction3::East]));
+        level.add_worm(Worm::new(Vector3i::new(1, 2, 0), [Direction3::East]));
+        level.link_portal(Vector3i::new(0, 7, 0), Vector3i::new(7, 7, 0));
+        level.link_portal(Vector3i::new(7, 7, 0), Vector3i::new(0, 7, 0));
+        assert_eq!(level.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_every_error() {
+        use Direction3::*;
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        level.add_wall(Vector3i::new(4, 4, 0));
+        level.add_worm(Worm::new(Vector3i::new(1, 1, 0), [East, East]));
+        level.add_worm(Worm::new(Vector3i::new(2, 0, 0), [North, North]));
+        level.add_worm(Worm::new(Vector3i::new(3, 4, 0), [East]));
+        level.add_worm(Worm::new(Vector3i::new(0, 6, 0), [West]));
+        level.add_worm(Worm::new(Vector3i::new(6, 6, 0), [East, North, West, South]));
+        level.link_portal(Vector3i::new(0, 7, 0), Vector3i::new(7, 7, 0));
+
+        let errors = level.validate().unwrap_err();
+        let expected = [
+            LevelError::WormsOverlap { first: WormId(0), second: WormId(1), cell: Vector3i::new(2, 1, 0) },
+            LevelError::WormInWall { worm: WormId(2), cell: Vector3i::new(4, 4, 0) },
+            LevelError::WormOutOfBounds { worm: WormId(3), cell: Vector3i::new(-1, 6, 0) },
+            LevelError::WormSelfIntersecting { worm: WormId(4), cell: Vector3i::new(6, 6, 0) },
+            LevelError::UnpairedPortal { entrance: Vector3i::new(0, 7, 0), exit: Vector3i::new(7, 7, 0) },
+        ];
+        for error in &expected {
+            assert!(errors.contains(error), "{error:?} should be reported");
+        }
+        assert_eq!(errors.len(), expected.len(), "each problem should be reported once");
+    }
+}
+
+mod parse {
+    use super::*;
+    use crate::level::{parse::LevelParseError, validate::LevelError};
+
+    #[test]
+    fn test_ok() {
+        let level: Level = "
+            # a short corridor
+            bounds 6 1 1
+            wall 5 0 0
+            goal 4 0 0
+            portal 0 0 0 3 0 0
+            portal 3 0 0 0 0 0
+            worm 2 0 0 <
+        ".parse().unwrap();
+        assert_eq!(level.bounds(), Vector3i::new(6, 1, 1));
+        assert!(level.is_solid(Vector3i::new(5, 0, 0)));
+        assert!(level.is_goal(Vector3i::new(4, 0, 0)));
+        assert_eq!(level.portal_exit(Vector3i::new(0, 0, 0)), Some(Vector3i::new(3, 0, 0)));
+        assert_eq!(level.worms().collect::<Vec<_>>(), [(WormId(0), &Worm::new(Vector3i::new(2, 0, 0), [Direction3::West]))]);
+    }
+
+    #[test]
+    fn test_syntax() {
+        let result = "bounds 6 1 1\nwal 5 0 0".parse::<Level>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 2, .. })), "unknown keyword should be reported on its line");
+        let result = "wall 5 0 0".parse::<Level>();
+        assert!(matches!(result, Err(LevelParseError::Syntax { line: 1, .. })), "bounds should be required first");
+    }
+
+    #[test]
+    fn test_validates() {
+        let result = "bounds 6 1 1\nwall 2 0 0\nworm 2 0 0".parse::<Level>();
+        assert_eq!(result.unwrap_err(), LevelParseError::Invalid(vec![
+            LevelError::WormInWall { worm: WormId(0), cell: Vector3i::new(2, 0, 0) },
+        ]));
+    }
+
+    #[test]
+    fn test_error_display() {
+        let error: Box<dyn std::error::Error> = Box::new("bounds 6 1 1\nworm 1 0 0 <?".parse::<Level>().unwrap_err());
+        assert_eq!(error.to_string(), "line 2: invalid character: '?'");
+        let error: Box<dyn std::error::Error> = Box::new("bounds 6 1 1\nwall 2 0 0\nworm 2 0 0".parse::<Level>().unwrap_err());
+        assert_eq!(error.to_string(), "invalid level: worm 0 is inside a wall at (2, 0, 0)");
+    }
+}
+
+mod shortest_path {
+    use super::*;
+
+    /// ```text
+    /// . . .
+    /// . # .
+    /// S # E
+    /// ```
+    fn walled() -> Level {
+        let mut level = Level::new(Vector3i::new(3, 3, 1));
+        level.add_wall(Vector3i::new(1, 0, 0));
+        level.add_wall(Vector3i::new(1, 1, 0));
+        level
+    }
+
+    #[test]
+    fn test_around_wall() {
+        use Direction3::*;
+        let path = walled().shortest_path(Vector3i::new(0, 0, 0), Vector3i::new(2, 0, 0));
+        assert_eq!(path, Some(vec![North, North, East, East, South, South]));
+    }
+
+    #[test]
+    fn test_same_cell() {
+        let path = walled().shortest_path(Vector3i::new(0, 0, 0), Vector3i::new(0, 0, 0));
+        assert_eq!(path, Some(vec![]));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let mut level = walled();
+        level.add_wall(Vector3i::new(1, 2, 0));
+        assert_eq!(level.shortest_path(Vector3i::new(0, 0, 0), Vector3i::new(2, 0, 0)), None);
+        assert_eq!(level.shortest_path(Vector3i::new(0, 0, 0), Vector3i::new(1, 0, 0)), None, "walls can't be reached");
+    }
+}
+
+mod sever_at {
+    use super::*;
+
+    #[test]
+    fn test_middle() {
+        use Direction3::*;
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        let id = level.add_worm(Worm::new(Vector3i::new(0, 0, 0), [East, East, East, North, North]));
+        assert!(level.sever_at(id, Vector3i::new(2, 0, 0)));
+        assert_eq!(level.worms().collect::<Vec<_>>(), [(id, &Worm::new(Vector3i::new(3, 0, 0), [North, North]))], "tail side should survive in place");
+    }
+
+    #[test]
+    fn test_tail() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        let id = level.add_worm(Worm::new(Vector3i::new(0, 0, 0), [Direction3::East]));
+        assert!(level.sever_at(id, Vector3i::new(1, 0, 0)));
+        assert_eq!(level.worm_count(), 0, "severing at the tail should leave nothing");
+    }
+
+    #[test]
+    fn test_missed() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        let id = level.add_worm(Worm::new(Vector3i::new(0, 0, 0), [Direction3::East]));
+        assert!(!level.sever_at(id, Vector3i::new(5, 5, 0)));
+        assert_eq!(level.worm_count(), 1);
+    }
+}
+
+mod eq {
+    use super::*;
+
+    fn level() -> Level {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        for x in 0..8 {
+            level.add_wall(Vector3i::new(x, 7, 0));
+        }
+        level.add_goal(Vector3i::new(6, 6, 0));
+        level.link_portal(Vector3i::new(0, 0, 0), Vector3i::new(7, 0, 0));
+        level.link_portal(Vector3i::new(7, 0, 0), Vector3i::new(0, 0, 0));
+        level.add_worm(Worm::new(Vector3i::new(2, 2, 0), [Direction3::West, Direction3::South]));
+        level
+    }
+
+    #[test]
+    fn test_clone() {
+        let original = level();
+        assert_eq!(original.clone(), original);
+        assert_eq!(level(), original, "levels built the same way should be equal regardless of set ordering");
+    }
+
+    #[test]
+    fn test_moved_worm() {
+        let original = level();
+        let mut moved = original.clone();
+        moved.worm_mut(WormId(0)).unwrap().crawl(Direction3::North);
+        assert_ne!(moved, original);
+    }
+
+    #[test]
+    fn test_added_wall() {
+        let original = level();
+        let mut walled = original.clone();
+        walled.add_wall(Vector3i::new(4, 4, 0));
+        assert_ne!(walled, original);
+    }
+}
+
+mod exposed_wall_faces {
+    use super::*;
+
+    #[test]
+    fn test_adjacent_walls() {
+        let mut level = Level::new(Vector3i::new(8, 8, 8));
+        level.add_wall(Vector3i::new(1, 1, 1));
+        level.add_wall(Vector3i::new(2, 1, 1));
+        let faces: Vec<_> = level.exposed_wall_faces().collect();
+        assert_eq!(faces.len(), 10, "the shared face should be hidden on both walls");
+        assert!(!faces.contains(&(Vector3i::new(1, 1, 1), Direction3::East)));
+        assert!(!faces.contains(&(Vector3i::new(2, 1, 1), Direction3::West)));
+        assert!(faces.contains(&(Vector3i::new(1, 1, 1), Direction3::West)));
+    }
+
+    #[test]
+    fn test_enclosed() {
+        let mut level = Level::new(Vector3i::new(8, 8, 8));
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    level.add_wall(Vector3i::new(x, y, z));
+                }
+            }
+        }
+        let faces: Vec<_> = level.exposed_wall_faces().collect();
+        assert_eq!(faces.len(), 6 * 9, "only the outer surface of a 3x3x3 block should be exposed");
+        assert!(faces.iter().all(|&(cell, _)| cell != Vector3i::new(1, 1, 1)), "the center cell has no exposed faces");
+    }
+}
+
+mod worm_ids_with {
+    use super::*;
+
+    fn two_worms() -> Level {
+        let mut level = Level::new(Vector3i::new(8, 8, 8));
+        level.add_worm(Worm::new(Vector3i::new(1, 1, 0), [Direction3::West]));
+        level.add_worm(Worm::new(Vector3i::new(4, 4, 0), [Direction3::West]));
+        level
+    }
+
+    #[test]
+    fn test_all() {
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::YOU)]);
+        assert_eq!(two_worms().worm_ids_with(Property::YOU, &rules), vec![WormId(0), WormId(1)]);
+    }
+
+    #[test]
+    fn test_none() {
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::PUSH)]);
+        assert!(two_worms().worm_ids_with(Property::YOU, &rules).is_empty(), "no worm is YOU without [WORM, IS, YOU]");
+    }
+}
+
+mod display {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut level = Level::new(Vector3i::new(6, 2, 1));
+        level.add_wall(Vector3i::new(5, 0, 0));
+        level.add_wall(Vector3i::new(5, 1, 0));
+        level.add_goal(Vector3i::new(4, 0, 0));
+        level.link_portal(Vector3i::new(0, 1, 0), Vector3i::new(3, 1, 0));
+        level.link_portal(Vector3i::new(3, 1, 0), Vector3i::new(0, 1, 0));
+        level.add_worm(Worm::new(Vector3i::new(2, 0, 0), [Direction3::West, Direction3::North]));
+        level.add_worm(Worm::new(Vector3i::new(4, 1, 0), []));
+        let text = level.to_string();
+        assert_eq!(text.parse::<Level>(), Ok(level.clone()));
+        let mut reordered = Level::new(level.bounds());
+        for cell in level.walls().collect::<Vec<_>>().into_iter().rev() {
+            reordered.add_wall(cell);
+        }
+        assert_eq!(
+            reordered.to_string().lines().filter(|line| line.starts_with("wall")).collect::<Vec<_>>(),
+            text.lines().filter(|line| line.starts_with("wall")).collect::<Vec<_>>(),
+            "output should not depend on insertion order",
+        );
+    }
+}
+
+mod text {
+    use super::*;
+    use crate::rules::{text::Text, statement::Statement};
+
+    fn text(word: &str) -> Text {
+        Text::new(word.parse().unwrap())
+    }
+
+    fn statement(words: &str) -> Statement {
+        words.split_whitespace().map(|word| word.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_statements() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        // WORM IS YOU
+        // IS
+        // WIN
+        level.add_text(Vector3i::new(0, 7, 0), text("WORM"));
+        level.add_text(Vector3i::new(1, 7, 0), text("IS"));
+        level.add_text(Vector3i::new(2, 7, 0), text("YOU"));
+        level.add_text(Vector3i::new(0, 6, 0), text("IS"));
+        level.add_text(Vector3i::new(0, 5, 0), text("WIN"));
+        // Too short to be a line
+        level.add_text(Vector3i::new(5, 2, 0), text("GOAL"));
+        level.add_text(Vector3i::new(6, 2, 0), text("IS"));
+        assert_eq!(level.statements(), [statement("WORM IS YOU"), statement("WORM IS WIN")]);
+    }
+
+    #[test]
+    fn test_push() {
+        let mut level = Level::new(Vector3i::new(8, 1, 1));
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        level.add_text(Vector3i::new(2, 0, 0), text("WORM"));
+        level.add_text(Vector3i::new(3, 0, 0), text("IS"));
+        let rules = Ruleset::from_iter([(Noun::TEXT, Property::PUSH)]);
+        let pushed = level.try_push(Vector3i::new(2, 0, 0), Direction3::East, WormId(0), &rules).unwrap();
+        assert_eq!(pushed.worms, []);
+        assert_eq!(pushed.texts.len(), 2);
+        assert_eq!(level.text_at(Vector3i::new(2, 0, 0)), None);
+        assert_eq!(level.text_at(Vector3i::new(3, 0, 0)), Some(text("WORM").moved_to(Vector3i::new(3, 0, 0))), "the block should know its new cell");
+        assert_eq!(level.text_at(Vector3i::new(4, 0, 0)), Some(text("IS").moved_to(Vector3i::new(4, 0, 0))));
+    }
+
+    #[test]
+    fn test_push_blocked() {
+        let mut level = Level::new(Vector3i::new(8, 1, 1));
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        level.add_text(Vector3i::new(2, 0, 0), text("WORM"));
+        level.add_wall(Vector3i::new(3, 0, 0));
+        let rules = Ruleset::from_iter([(Noun::TEXT, Property::PUSH)]);
+        assert_eq!(level.try_push(Vector3i::new(2, 0, 0), Direction3::East, WormId(0), &rules), None, "text against a wall can't move");
+        assert_eq!(level.try_push(Vector3i::new(2, 0, 0), Direction3::West, WormId(0), &Ruleset::new()), None, "text without PUSH can't move");
+        assert_eq!(level.text_at(Vector3i::new(2, 0, 0)).map(|text| text.word()), Some(text("WORM").word()));
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let level: Level = "bounds 4 1 1\ntext 0 0 0 WORM\ntext 1 0 0 IS\ntext 2 0 0 YOU".parse().unwrap();
+        assert_eq!(level.statements(), [statement("WORM IS YOU")]);
+        assert_eq!(level.to_string().parse::<Level>(), Ok(level));
+    }
+}
+
+mod remove_worm {
+    use super::*;
+
+    #[test]
+    fn test_ids_stable() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        let a = level.add_worm(Worm::new(Vector3i::new(1, 1, 0), [Direction3::West]));
+        let b = level.add_worm(Worm::new(Vector3i::new(4, 4, 0), [Direction3::West]));
+        assert_eq!(level.remove_worm(a), Some(Worm::new(Vector3i::new(1, 1, 0), [Direction3::West])));
+        assert_eq!(level.remove_worm(a), None, "a worm can only be removed once");
+        assert_eq!(level.worm(b), Some(&Worm::new(Vector3i::new(4, 4, 0), [Direction3::West])));
+        let c = level.add_worm(Worm::new(Vector3i::new(1, 1, 0), []));
+        assert_ne!(c, a, "ids should not be reused");
+        assert_eq!(level.worms().map(|(id, _)| id).collect::<Vec<_>>(), [b, c]);
+    }
+}
+
+mod compact {
+    use super::*;
+
+    #[test]
+    fn test_after_destroying() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        let ids = [1, 3, 5].map(|y| level.add_worm(Worm::from_str(Vector3i::new(2, y, 0), "<<").unwrap()));
+        level.remove_worm(ids[1]);
+        let before = level.clone();
+        level.compact();
+        assert_eq!(level, before, "compacting should never change the level");
+        assert_eq!(level.worm_count(), 2);
+        assert_eq!(level.worm_at(Vector3i::new(0, 5, 0)), Some(ids[2]));
+        assert_eq!(level.cells.iter().count(), 6, "only the two remaining worms' cells should be occupied");
+        assert!(level.cells.iter().all(|(cell, object)| object.noun() == Noun::WORM && level.worm_at(cell).is_some()), "no dangling cells");
+    }
+
+    #[test]
+    fn test_shrinks() {
+        let mut level = Level::new(Vector3i::new(64, 1, 1));
+        for x in 0..64 {
+            level.add_wall(Vector3i::new(x, 0, 0));
+        }
+        let capacity = level.cells.capacity();
+        assert_eq!(level.take_all(Noun::WALL).len(), 64);
+        level.compact();
+        assert!(level.cells.capacity() < capacity, "the emptied cells should give back their memory");
+    }
+}
+
+mod apply_transformations {
+    use super::*;
+
+    fn transforming(from: Noun, into: Noun) -> Ruleset {
+        let mut rules = Ruleset::new();
+        rules.insert_transformation(from, into);
+        rules
+    }
+
+    #[test]
+    fn test_wall_is_worm() {
+        let mut level = Level::new(Vector3i::new(4, 4, 1));
+        level.add_wall(Vector3i::new(1, 1, 0));
+        level.add_wall(Vector3i::new(2, 1, 0));
+        assert!(level.apply_transformations(&transforming(Noun::WALL, Noun::WORM)));
+        assert_eq!(level.walls().count(), 0);
+        let worms: Vec<_> = level.worms().map(|(_, worm)| worm.clone()).collect();
+        assert_eq!(worms, [
+            Worm::new(Vector3i::new(1, 1, 0), []),
+            Worm::new(Vector3i::new(2, 1, 0), []),
+        ]);
+    }
+
+    #[test]
+    fn test_worm_is_worm() {
+        let mut level = Level::new(Vector3i::new(4, 4, 1));
+        level.add_worm(Worm::from_str(Vector3i::new(0, 0, 0), ">>^").unwrap());
+        let before = level.clone();
+        assert!(!level.apply_transformations(&transforming(Noun::WORM, Noun::WORM)), "nothing should count as transformed");
+        assert_eq!(level, before);
+    }
+
+    #[test]
+    fn test_noun_is_itself_blocks() {
+        let mut level = Level::new(Vector3i::new(4, 4, 1));
+        level.add_wall(Vector3i::new(1, 1, 0));
+        let mut rules = transforming(Noun::WALL, Noun::WORM);
+        rules.insert_transformation(Noun::WALL, Noun::WALL);
+        let before = level.clone();
+        assert!(!level.apply_transformations(&rules));
+        assert_eq!(level, before);
+    }
+
+    #[test]
+    fn test_nothing_to_transform() {
+        let mut level = Level::new(Vector3i::new(4, 4, 1));
+        level.add_goal(Vector3i::new(1, 1, 0));
+        assert!(!level.apply_transformations(&transforming(Noun::WALL, Noun::WORM)), "there are no walls");
+    }
+
+    #[test]
+    fn test_no_chaining() {
+        let mut level = Level::new(Vector3i::new(4, 4, 1));
+        level.add_wall(Vector3i::new(1, 1, 0));
+        level.add_goal(Vector3i::new(2, 2, 0));
+        let mut rules = transforming(Noun::WALL, Noun::GOAL);
+        rules.insert_transformation(Noun::GOAL, Noun::TEXT);
+        level.apply_transformations(&rules);
+        assert_eq!(level.goals().collect::<Vec<_>>(), [Vector3i::new(1, 1, 0)]);
+        assert_eq!(level.text_at(Vector3i::new(2, 2, 0)).map(|text| text.word()), Some(Word::Noun(Noun::GOAL)));
+        assert_eq!(level.walls().count(), 0);
+    }
+}
+
+mod hash {
+    use super::*;
+
+    fn hash_of(level: &Level) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        level.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_equal_levels() {
+        let cells = (0..16).map(|i| Vector3i::new(i % 4, i / 4, 0));
+        let mut a = Level::new(Vector3i::new(4, 4, 1));
+        let mut b = Level::new(Vector3i::new(4, 4, 1));
+        for cell in cells.clone() {
+            a.add_wall(cell);
+        }
+        for cell in cells.rev() {
+            b.add_wall(cell);
+        }
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b), "insertion order should not matter");
+    }
+
+    #[test]
+    fn test_moved_worm() {
+        let mut level = Level::new(Vector3i::new(4, 4, 1));
+        let id = level.add_worm(Worm::new(Vector3i::new(1, 1, 0), [Direction3::West]));
+        let before = hash_of(&level);
+        level.worm_mut(id).unwrap().crawl(Direction3::North);
+        assert_ne!(hash_of(&level), before);
+    }
+}
+
+mod bounding_box {
+    use super::*;
+
+    #[test]
+    fn test_two_worms() {
+        let mut level = Level::new(Vector3i::new(16, 16, 4));
+        level.add_worm(Worm::from_str(Vector3i::new(2, 3, 0), ">>").unwrap());
+        level.add_worm(Worm::from_str(Vector3i::new(10, 12, 2), "vo").unwrap());
+        assert_eq!(level.bounding_box(), Some((Vector3i::new(2, 3, 0), Vector3i::new(10, 12, 2))));
+        level.add_wall(Vector3i::new(0, 15, 3));
+        assert_eq!(level.bounding_box(), Some((Vector3i::new(0, 3, 0), Vector3i::new(10, 15, 3))));
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut level = Level::new(Vector3i::new(4, 4, 1));
+        level.add_goal(Vector3i::new(1, 1, 0));
+        assert_eq!(level.bounding_box(), None, "goals don't count");
+    }
+}
+
+mod step_moving {
+    use super::*;
+
+    #[test]
+    fn test_bounces() {
+        let mut level = Level::new(Vector3i::new(6, 1, 1));
+        level.add_wall(Vector3i::new(0, 0, 0));
+        level.add_wall(Vector3i::new(5, 0, 0));
+        let id = level.add_worm(Worm::from_str(Vector3i::new(2, 0, 0), "<").unwrap());
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::MOVE)]);
+        let heads: Vec<_> = (0..6)
+            .map(|_| {
+                assert_eq!(level.step_moving(&rules), [id]);
+                level.worm(id).unwrap().head_position().x
+            })
+            .collect();
+        assert_eq!(heads, [3, 4, 2, 1, 3, 4], "should turn around at each wall");
+    }
+
+    #[test]
+    fn test_tailless() {
+        let mut level = Level::new(Vector3i::new(3, 1, 1));
+        let id = level.add_worm(Worm::new(Vector3i::new(1, 0, 0), []));
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::MOVE)]);
+        let heads: Vec<_> = (0..4)
+            .map(|_| {
+                level.step_moving(&rules);
+                level.worm(id).unwrap().head_position().x
+            })
+            .collect();
+        assert_eq!(heads, [2, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_stuck() {
+        let mut level = Level::new(Vector3i::new(2, 1, 1));
+        let id = level.add_worm(Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap());
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::MOVE)]);
+        assert!(level.step_moving(&rules).is_empty());
+        assert_eq!(level.worm(id), Some(&Worm::from_str(Vector3i::new(0, 0, 0), ">").unwrap()), "should still turn around");
+    }
+
+    #[test]
+    fn test_not_move() {
+        let mut level = Level::new(Vector3i::new(6, 1, 1));
+        level.add_worm(Worm::from_str(Vector3i::new(2, 0, 0), "<").unwrap());
+        let before = level.clone();
+        assert!(level.step_moving(&Ruleset::new()).is_empty());
+        assert_eq!(level, before);
+    }
+}
+
+mod raycast {
+    use super::*;
+
+    #[test]
+    fn test_hit() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        level.add_wall(Vector3i::new(5, 2, 0));
+        let worm = Worm::from_str(Vector3i::new(1, 2, 0), "<").unwrap();
+        let (from, direction) = worm.head_ray();
+        assert_eq!(level.raycast(from, direction, 8), Some(Vector3i::new(5, 2, 0)));
+        assert_eq!(level.raycast(from, direction, 4), Some(Vector3i::new(5, 2, 0)), "the wall is exactly 4 steps away");
+    }
+
+    #[test]
+    fn test_miss() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        level.add_wall(Vector3i::new(5, 2, 0));
+        assert_eq!(level.raycast(Vector3i::new(1, 2, 0), Direction3::East, 3), None);
+        assert_eq!(level.raycast(Vector3i::new(1, 2, 0), Direction3::North, 100), None, "leaving the level should stop the ray");
+    }
+
+    #[test]
+    fn test_worm() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        level.add_worm(Worm::from_str(Vector3i::new(3, 4, 0), "v").unwrap());
+        assert_eq!(level.raycast(Vector3i::new(0, 3, 0), Direction3::East, 8), Some(Vector3i::new(3, 3, 0)));
+    }
+}
+
+mod rotate_region {
+    use super::*;
+    use crate::spacial::axis3i::Axis3i;
+
+    fn level() -> (Level, WormId, WormId) {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        level.add_wall(Vector3i::new(4, 3, 0));
+        level.add_wall(Vector3i::new(7, 7, 0));
+        let inside = level.add_worm(Worm::from_str(Vector3i::new(2, 4, 0), ">v").unwrap());
+        let straddling = level.add_worm(Worm::from_str(Vector3i::new(5, 1, 0), "<").unwrap());
+        (level, inside, straddling)
+    }
+
+    #[test]
+    fn test_quarter_turn() {
+        let (mut level, inside, straddling) = level();
+        let before = level.clone();
+        level.rotate_region(Vector3i::new(3, 3, 0), 1, Axis3i::Z, 1);
+        assert_eq!(level.walls().collect::<HashSet<_>>(), HashSet::from([Vector3i::new(3, 2, 0), Vector3i::new(7, 7, 0)]));
+        assert_eq!(
+            level.worm(inside).unwrap().segment_positions().collect::<Vec<_>>(),
+            [Vector3i::new(4, 4, 0), Vector3i::new(4, 3, 0), Vector3i::new(3, 3, 0)],
+        );
+        assert_eq!(level.worm(straddling), before.worm(straddling), "worms sticking out of the region should stay put");
+    }
+
+    #[test]
+    fn test_four_turns() {
+        let (mut level, ..) = level();
+        let before = level.clone();
+        for _ in 0..4 {
+            level.rotate_region(Vector3i::new(3, 3, 0), 1, Axis3i::Z, 1);
+        }
+        assert_eq!(level, before);
+    }
+}
+
+mod objects_at {
+    use super::*;
+
+    #[test]
+    fn test_layers() {
+        let mut level = Level::new(Vector3i::new(4, 4, 1));
+        let cell = Vector3i::new(1, 1, 0);
+        level.add_goal(cell);
+        level.add_text(cell, Text::new(Word::Property(Property::WIN)));
+        let worm = level.add_worm(Worm::from_str(Vector3i::new(2, 1, 0), "<").unwrap());
+        assert_eq!(level.objects_at(cell), [Object::Goal, Object::Text(Text::placed(Word::Property(Property::WIN), cell, Direction3::East)), Object::Worm(worm)]);
+        assert!(level.is_goal(cell), "the worm should not hide the goal");
+        assert_eq!(level.worm_at(cell), Some(worm));
+        assert_eq!(level.objects_at(cell).iter().copied().map(Object::noun).collect::<Vec<_>>(), [Noun::GOAL, Noun::TEXT, Noun::WORM]);
+        assert!(level.objects_at(Vector3i::new(0, 0, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_push_off_goal() {
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::PUSH)]);
+        let mut level = Level::new(Vector3i::new(6, 1, 1));
+        let goal = Vector3i::new(3, 0, 0);
+        level.add_goal(goal);
+        let pusher = level.add_worm(Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap());
+        let pushed = level.add_worm(Worm::from_str(goal, "<").unwrap());
+        assert_eq!(level.objects_at(goal), [Object::Goal, Object::Worm(pushed)]);
+        assert_eq!(level.try_push(Vector3i::new(2, 0, 0), Direction3::East, pusher, &rules).unwrap().worms, [pushed]);
+        assert_eq!(level.objects_at(goal), [Object::Goal, Object::Worm(pushed)], "the tail should have moved onto the goal");
+        assert_eq!(level.objects_at(Vector3i::new(4, 0, 0)), [Object::Worm(pushed)]);
+        assert!(level.objects_at(Vector3i::new(2, 0, 0)).is_empty(), "the goal should stay put");
+    }
+
+    #[test]
+    fn test_filtered_by_property() {
+        let mut level = Level::new(Vector3i::new(4, 1, 1));
+        let cell = Vector3i::new(2, 0, 0);
+        level.add_wall(cell);
+        level.add_text(cell, Text::new(Word::Noun(Noun::WALL)));
+        let worm = level.add_worm(Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap());
+        let mut moved = level.worm(worm).unwrap().clone();
+        assert_eq!(moved.try_crawl_in(Direction3::East, &level, &Ruleset::new()), Err(crate::worm::CrawlBlocked::Solid(cell)));
+        let rules = Ruleset::from_iter([(Noun::WALL, Property::FLOAT)]);
+        assert_eq!(moved.try_crawl_in(Direction3::East, &level, &rules), Err(crate::worm::CrawlBlocked::Occupied(cell)), "the text should still block");
+        let rules = Ruleset::from_iter([(Noun::WALL, Property::FLOAT), (Noun::TEXT, Property::FLOAT)]);
+        assert!(moved.try_crawl_in(Direction3::East, &level, &rules).is_ok(), "nothing left interacts with the worm");
+
+        let rules = Ruleset::from_iter([(Noun::WALL, Property::FLOAT), (Noun::TEXT, Property::PUSH)]);
+        let pushed = level.try_push(cell, Direction3::East, worm, &rules).unwrap();
+        assert_eq!(pushed.texts, [cell], "the text should be pushed, ignoring the floating wall");
+        assert_eq!(level.text_at(cell + Direction3::East).map(|text| text.word()), Some(Word::Noun(Noun::WALL)));
+        let mut moved = level.worm(worm).unwrap().clone();
+        let outcome = moved.try_crawl_in(Direction3::East, &level, &rules).unwrap();
+        level.set_crawled_worm(worm, moved, outcome);
+        assert_eq!(level.objects_at(cell), [Object::Wall, Object::Worm(worm)]);
+    }
+}
+
+mod delta {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut prev = Level::new(Vector3i::new(8, 8, 1));
+        prev.add_wall(Vector3i::new(0, 0, 0));
+        prev.add_goal(Vector3i::new(7, 7, 0));
+        prev.add_text(Vector3i::new(1, 6, 0), Text::new(Word::Noun(Noun::WORM)));
+        let moved = prev.add_worm(Worm::from_str(Vector3i::new(2, 2, 0), "<<").unwrap());
+        let removed = prev.add_worm(Worm::from_str(Vector3i::new(2, 4, 0), "<").unwrap());
+        prev.add_worm(Worm::from_str(Vector3i::new(5, 5, 0), "v").unwrap());
+
+        let mut curr = prev.clone();
+        curr.worm_mut(moved).unwrap().crawl(Direction3::North);
+        curr.remove_worm(removed);
+        curr.add_worm(Worm::new(Vector3i::new(6, 1, 0), []));
+        curr.apply_transformations(&{
+            let mut rules = Ruleset::new();
+            rules.insert_transformation(Noun::WALL, Noun::GOAL);
+            rules
+        });
+        curr.cells.remove(Vector3i::new(1, 6, 0), |object| matches!(object, Object::Text(_)));
+        curr.add_text(Vector3i::new(2, 6, 0), Text::new(Word::Noun(Noun::WORM)));
+
+        let delta = curr.delta(&prev);
+        assert_eq!(delta.removed_worms(), [removed]);
+        assert_eq!(delta.changed_worms().count(), 2, "only the moved and added worms should be sent");
+        prev.apply_delta(&delta);
+        assert_eq!(prev, curr);
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let mut level = Level::new(Vector3i::new(4, 4, 1));
+        level.add_worm(Worm::from_str(Vector3i::new(1, 1, 0), "<").unwrap());
+        assert!(level.delta(&level.clone()).is_empty());
+    }
+}
+
+mod reachable_from {
+    use super::*;
+
+    fn divided() -> Level {
+        let mut level = Level::new(Vector3i::new(5, 3, 1));
+        for y in 0..3 {
+            level.add_wall(Vector3i::new(2, y, 0));
+        }
+        level
+    }
+
+    #[test]
+    fn test_divided() {
+        let mut level = divided();
+        let reached = level.reachable_from(Vector3i::new(0, 0, 0));
+        assert_eq!(reached.len(), 6, "only the left side should be reachable");
+        assert!(!reached.contains(&Vector3i::new(4, 0, 0)));
+        assert!(!level.is_fully_connected());
+
+        level.cells.remove(Vector3i::new(2, 1, 0), |object| object == Object::Wall);
+        assert_eq!(level.reachable_from(Vector3i::new(0, 0, 0)).len(), 13);
+        assert!(level.is_fully_connected(), "a gap should join the two sides");
+    }
+
+    #[test]
+    fn test_blocked_start() {
+        let level = divided();
+        assert!(level.reachable_from(Vector3i::new(2, 0, 0)).is_empty(), "a wall can't be stood in");
+        assert!(level.reachable_from(Vector3i::new(-1, 0, 0)).is_empty());
+    }
+}
+
+mod worm_at {
+    use super::*;
+
+    #[test]
+    fn test_cells() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        let first = level.add_worm(Worm::from_str(Vector3i::new(3, 3, 0), "<<v").unwrap());
+        let second = level.add_worm(Worm::from_str(Vector3i::new(5, 5, 0), ">").unwrap());
+        assert_eq!(level.worm_at(Vector3i::new(3, 3, 0)), Some(first), "head");
+        assert_eq!(level.worm_at(Vector3i::new(2, 3, 0)), Some(first), "body");
+        assert_eq!(level.worm_at(Vector3i::new(1, 2, 0)), Some(first), "tail");
+        assert_eq!(level.worm_at(Vector3i::new(6, 5, 0)), Some(second));
+        assert_eq!(level.worm_at(Vector3i::new(4, 4, 0)), None);
+    }
+
+    #[test]
+    fn test_after_move() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        let worm = level.add_worm(Worm::from_str(Vector3i::new(3, 3, 0), "<").unwrap());
+        assert_eq!(level.worm_at(Vector3i::new(2, 3, 0)), Some(worm));
+        level.worm_mut(worm).unwrap().crawl(Direction3::North);
+        assert_eq!(level.worm_at(Vector3i::new(2, 3, 0)), None, "the old tail cell should be free");
+        assert_eq!(level.worm_at(Vector3i::new(3, 4, 0)), Some(worm));
+        level.remove_worm(worm);
+        assert_eq!(level.worm_at(Vector3i::new(3, 4, 0)), None);
+    }
+
+    /// Every cell agrees with the worms' own cells.
+    fn assert_cells_agree(level: &Level) {
+        for cell in Vector3i::iter_box(Vector3i::new(0, 0, 0), level.bounds() - 1) {
+            let expected = level.worms().find(|(_, worm)| worm.occupies(cell)).map(|(id, _)| id);
+            assert_eq!(level.worm_at(cell), expected, "{cell}");
+        }
+    }
+
+    #[test]
+    fn test_kept_after_push() {
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::PUSH)]);
+        let mut level = Level::new(Vector3i::new(8, 1, 1));
+        let pusher = level.add_worm(Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap());
+        level.add_worm(Worm::from_str(Vector3i::new(3, 0, 0), "<").unwrap());
+        level.add_worm(Worm::from_str(Vector3i::new(5, 0, 0), "<").unwrap());
+        assert_eq!(level.worm_at(Vector3i::new(0, 0, 0)), Some(pusher));
+        let pushed = level.try_push(Vector3i::new(2, 0, 0), Direction3::East, pusher, &rules).unwrap();
+        assert_eq!(pushed.worms.len(), 2, "both worms should be pushed into each other's cells");
+        assert_cells_agree(&level);
+    }
+
+    #[test]
+    fn test_kept_after_blocked_move() {
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::MOVE)]);
+        let mut level = Level::new(Vector3i::new(2, 1, 1));
+        let worm = level.add_worm(Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap());
+        assert_eq!(level.worm_at(Vector3i::new(0, 0, 0)), Some(worm));
+        assert_eq!(level.step_moving(&rules), [], "there's no room to move either way");
+        assert_cells_agree(&level);
+    }
+
+    #[test]
+    fn test_kept_after_remove_and_sever() {
+        let mut level = Level::new(Vector3i::new(8, 1, 1));
+        let first = level.add_worm(Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap());
+        let second = level.add_worm(Worm::from_str(Vector3i::new(5, 0, 0), "<<").unwrap());
+        assert_eq!(level.worm_at(Vector3i::new(0, 0, 0)), Some(first));
+        level.remove_worm(first);
+        assert!(level.sever_at(second, Vector3i::new(4, 0, 0)));
+        assert_cells_agree(&level);
+    }
+
+    #[test]
+    fn test_kept_after_replace() {
+        let mut level = Level::new(Vector3i::new(8, 1, 1));
+        let first = level.add_worm(Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap());
+        let second = level.add_worm(Worm::from_str(Vector3i::new(3, 0, 0), "<").unwrap());
+        assert_eq!(level.worm_at(Vector3i::new(0, 0, 0)), Some(first));
+        // Both step east at once, the first into the cell the second leaves
+        level.replace_worms([first, second].map(|id| {
+            let mut worm = level.worm(id).unwrap().clone();
+            worm.crawl(Direction3::East);
+            (id, worm)
+        }));
+        assert_eq!(level.worm_at(Vector3i::new(2, 0, 0)), Some(first));
+        assert_cells_agree(&level);
+    }
+
+    #[test]
+    fn test_after_set_crawled() {
+        let rules = Ruleset::new();
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        let worm = level.add_worm(Worm::from_str(Vector3i::new(3, 3, 0), "<<").unwrap());
+        assert_eq!(level.worm_at(Vector3i::new(1, 3, 0)), Some(worm));
+        for direction in [Direction3::North, Direction3::South, Direction3::West] {
+            let mut moved = level.worm(worm).unwrap().clone();
+            let outcome = moved.try_crawl_in(direction, &level, &rules).unwrap();
+            level.set_crawled_worm(worm, moved, outcome);
+            for (x, y) in (0..8).flat_map(|x| (0..8).map(move |y| (x, y))) {
+                let cell = Vector3i::new(x, y, 0);
+                let expected = level.worm(worm).unwrap().occupies(cell).then_some(worm);
+                assert_eq!(level.worm_at(cell), expected, "{cell} after crawling {direction:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_order_independent() {
+        let cell = Vector3i::new(2, 3, 0);
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        level.add_worm(Worm::from_str(Vector3i::new(3, 3, 0), "<").unwrap());
+        level.add_goal(cell);
+        let mut other = Level::new(Vector3i::new(8, 8, 1));
+        other.add_goal(cell);
+        other.add_worm(Worm::from_str(Vector3i::new(3, 3, 0), "<").unwrap());
+        assert_eq!(level, other, "a cell's objects should be kept in the same order whichever was added first");
+    }
+}
+
+mod spawn_worm {
+    use super::*;
+
+    #[test]
+    fn test_spawn() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        let existing = level.add_worm(Worm::from_str(Vector3i::new(1, 1, 0), "<").unwrap());
+        assert_eq!(level.worm_at(Vector3i::new(1, 1, 0)), Some(existing));
+        let spawned = level.spawn_worm(Worm::from_str(Vector3i::new(4, 4, 0), "v<").unwrap()).unwrap();
+        assert_ne!(spawned, existing);
+        assert_eq!(level.worm_at(Vector3i::new(4, 4, 0)), Some(spawned), "head");
+        assert_eq!(level.worm_at(Vector3i::new(3, 3, 0)), Some(spawned), "tail");
+        assert_eq!(level.worm_at(Vector3i::new(1, 1, 0)), Some(existing));
+    }
+
+    #[test]
+    fn test_rejected() {
+        let mut level = Level::new(Vector3i::new(8, 8, 1));
+        let existing = level.add_worm(Worm::from_str(Vector3i::new(1, 1, 0), "<").unwrap());
+        level.add_wall(Vector3i::new(5, 5, 0));
+        let before = level.clone();
+        assert_eq!(
+            level.spawn_worm(Worm::from_str(Vector3i::new(2, 2, 0), "v<").unwrap()),
+            Err(SpawnError::Overlaps { other: existing, cell: Vector3i::new(1, 1, 0) }),
+        );
+        assert_eq!(
+            level.spawn_worm(Worm::from_str(Vector3i::new(5, 4, 0), "^").unwrap()),
+            Err(SpawnError::InWall { cell: Vector3i::new(5, 5, 0) }),
+        );
+        assert_eq!(level, before, "nothing should be added");
+        assert_eq!(level.worm_at(Vector3i::new(2, 2, 0)), None);
+    }
+
+    #[test]
+    fn test_invalid_worm() {
+        let mut level = Level::new(Vector3i::new(4, 4, 1));
+        assert_eq!(
+            level.spawn_worm(Worm::from_str(Vector3i::new(0, 0, 0), ">>>>").unwrap()),
+            Err(SpawnError::OutOfBounds { cell: Vector3i::new(4, 0, 0) }),
+        );
+        assert_eq!(
+            level.spawn_worm(Worm::from_str(Vector3i::new(1, 1, 0), "<^>v").unwrap()),
+            Err(SpawnError::SelfIntersecting { cell: Vector3i::new(1, 1, 0) }),
+        );
+        assert_eq!(level.worm_count(), 0);
+        let id = level.spawn_worm(Worm::from_str(Vector3i::new(3, 3, 0), "<<<").unwrap()).unwrap();
+        assert_eq!(level.validate(), Ok(()), "a spawned worm should never make the level invalid");
+        assert_eq!(level.worm_at(Vector3i::new(0, 3, 0)), Some(id));
+    }
+}
@@ -0,0 +1,118 @@
+//! Consistency checks for a [`Level`].
+
+use std::{collections::HashMap, fmt};
+use crate::spacial::vector3i::Vector3i;
+use super::{Level, WormId};
+
+/// A way in which a [`Level`] is inconsistent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelError {
+    /// Part of a worm is outside [`Level::bounds()`].
+    WormOutOfBounds {
+        /// The worm.
+        worm: WormId,
+        /// The first of its cells found out of bounds.
+        cell: Vector3i,
+    },
+    /// Part of a worm is inside a wall.
+    WormInWall {
+        /// The worm.
+        worm: WormId,
+        /// The first of its cells found in a wall.
+        cell: Vector3i,
+    },
+    /// A worm crosses over itself.
+    WormSelfIntersecting {
+        /// The worm.
+        worm: WormId,
+        /// The first cell found occupied twice.
+        cell: Vector3i,
+    },
+    /// Two worms share a cell.
+    WormsOverlap {
+        /// The earlier worm in [`Level::worms()`].
+        first: WormId,
+        /// The later worm in [`Level::worms()`].
+        second: WormId,
+        /// The first shared cell found.
+        cell: Vector3i,
+    },
+    /// A portal's exit does not lead back to it.
+    UnpairedPortal {
+        /// The portal.
+        entrance: Vector3i,
+        /// Where it leads.
+        exit: Vector3i,
+    },
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WormOutOfBounds { worm, cell } => write!(f, "worm {} is out of bounds at {cell}", worm.0),
+            Self::WormInWall { worm, cell } => write!(f, "worm {} is inside a wall at {cell}", worm.0),
+            Self::WormSelfIntersecting { worm, cell } => write!(f, "worm {} crosses itself at {cell}", worm.0),
+            Self::WormsOverlap { first, second, cell } => write!(f, "worms {} and {} overlap at {cell}", first.0, second.0),
+            Self::UnpairedPortal { entrance, exit } => write!(f, "portal at {entrance} leads to {exit}, which does not lead back"),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl Level {
+    /// Check that the level is self-consistent before simulating it.
+    /// Every problem is reported, not just the first.
+    ///
+    /// Each worm and each pair of worms is reported at most once per kind of error.
+    pub fn validate(&self) -> Result<(), Vec<LevelError>> {
+        let mut errors = Vec::new();
+
+        let mut owners = HashMap::<Vector3i, WormId>::new();
+        let mut overlapping = Vec::<(WormId, WormId)>::new();
+        for (id, worm) in self.worms() {
+            let mut out_of_bounds = None;
+            let mut in_wall = None;
+            let mut self_intersecting = None;
+            for cell in worm.segment_positions() {
+                if !self.in_bounds(cell) {
+                    out_of_bounds.get_or_insert(cell);
+                }
+                if self.is_solid(cell) {
+                    in_wall.get_or_insert(cell);
+                }
+                match owners.get(&cell) {
+                    Some(&owner) if owner == id => {
+                        self_intersecting.get_or_insert(cell);
+                    },
+                    Some(&owner) => if !overlapping.contains(&(owner, id)) {
+                        overlapping.push((owner, id));
+                        errors.push(LevelError::WormsOverlap { first: owner, second: id, cell });
+                    },
+                    None => {
+                        owners.insert(cell, id);
+                    },
+                }
+            }
+            errors.extend(out_of_bounds.map(|cell| LevelError::WormOutOfBounds { worm: id, cell }));
+            errors.extend(in_wall.map(|cell| LevelError::WormInWall { worm: id, cell }));
+            errors.extend(self_intersecting.map(|cell| LevelError::WormSelfIntersecting { worm: id, cell }));
+        }
+
+        let mut unpaired: Vec<_> = self.portals()
+            .filter(|&(entrance, exit)| self.portal_exit(exit) != Some(entrance))
+            .collect();
+        // HashMap order is arbitrary
+        unpaired.sort_by_key(|(entrance, _)| (entrance.z, entrance.y, entrance.x));
+        errors.extend(unpaired
+            .into_iter()
+            .map(|(entrance, exit)| LevelError::UnpairedPortal { entrance, exit })
+        );
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,16 @@
+//! Puzzle game inspired by *Can of Wormholes* and *BABA IS YOU*.
+//!
+//! Everything except [`render`] is headless, and builds without the `raylib` feature.
+
+#![warn(missing_docs)]
+
+pub mod spacial;
+pub mod rules;
+pub mod worm;
+pub mod level;
+pub mod game_state;
+pub mod input;
+pub mod save;
+pub mod solver;
+#[cfg(feature = "raylib")]
+pub mod render;
@@ -1,39 +1,19 @@
 //! Puzzle game inspired by *Can of Wormholes* and *BABA IS YOU*.
 
-#![warn(missing_docs)]
-
 use raylib::prelude::*;
 
-pub mod spacial;
-pub mod rules;
-pub mod worm;
-use spacial::{direction3::Direction3, vector3i::Vector3i};
-use worm::*;
-
-/// The sidelength of a cell in the game's grid.
-pub const CELL_SIZE: f32 = 8.0;
-
-/// [`CELL_SIZE`] as a [`Vector3`].
-pub const VOXEL: Vector3 = Vector3::new(CELL_SIZE, CELL_SIZE, CELL_SIZE);
-
-/// Converts from grid coordinates to world coordinates.
-pub fn cell_to_world(cell: Vector3i) -> Vector3 {
-    Vector3::new(
-        cell.x as f32 * CELL_SIZE,
-        cell.y as f32 * CELL_SIZE,
-        cell.z as f32 * CELL_SIZE,
-    )
-}
+use puzzle_game_rs::{
+    spacial::{direction3::Direction3, vector3i::Vector3i, axis3i::Axis3i},
+    worm::*,
+    level::Level,
+    game_state::{GameState, StepReport, format_report},
+    input::{InputBuffer, TICK_INTERVAL},
+    render::{RenderConfig, SegmentStyle, Orbit, draw_walls, draw_segment, shade_segment, orbit_camera, interpolate_worm, LIGHT_DIRECTION},
+    rules::{ruleset::Ruleset, word::{noun::Noun, property::Property}},
+};
 
-/// Converts from world coordinates to grid coordinates.
-pub fn world_to_cell(position: Vector3) -> Vector3i {
-    const RECIPROCAL_CELL_SIZE: f32 = 1.0 / CELL_SIZE;
-    Vector3i::new(
-        (position.x * RECIPROCAL_CELL_SIZE).round() as i32,
-        (position.y * RECIPROCAL_CELL_SIZE).round() as i32,
-        (position.z * RECIPROCAL_CELL_SIZE).round() as i32,
-    )
-}
+/// How fast holding an orbit key swings the camera, in radians per second.
+const ORBIT_SPEED: f32 = 1.5;
 
 fn main() {
     let (mut rl, thread) = init()
@@ -41,8 +21,12 @@ fn main() {
         .title("puzzle game")
         .build();
 
-    let mut worm = Worm::new(
-        Vector3i::new(0, 0, 0),
+    let render_config = RenderConfig::default();
+
+    let mut level = Level::new(Vector3i::new(8, 8, 1));
+
+    level.add_worm(Worm::new(
+        Vector3i::new(1, 5, 0),
         [
             Direction3::South,
             Direction3::East,
@@ -55,14 +39,37 @@ fn main() {
             Direction3::West,
             Direction3::West,
         ]
-    );
+    ));
+
+    for i in 0..8 {
+        level.add_wall(Vector3i::new(i, 0, 0));
+        level.add_wall(Vector3i::new(i, 7, 0));
+        level.add_wall(Vector3i::new(0, i, 0));
+        level.add_wall(Vector3i::new(7, i, 0));
+    }
+
+    level.add_goal(Vector3i::new(3, 3, 0));
+
+    let mut game = GameState::new(level, Ruleset::from_iter([
+        (Noun::WORM, Property::YOU),
+        (Noun::WORM, Property::PUSH),
+        (Noun::GOAL, Property::WIN),
+    ]));
 
-    let camera = Camera3D::perspective(
-        Vector3::new(0.0, 0.0, CELL_SIZE * 8.0),
-        Vector3::zero(),
-        Vector3::new(0.0, 1.0, 0.0),
-        90.0,
-    );
+    let camera_target = render_config.cell_to_world(game.level().bounds() / 2);
+    let camera_distance = render_config.cell_size * 8.0;
+    let mut camera = Camera3D::perspective(Vector3::zero(), Vector3::zero(), Vector3::zero(), 90.0);
+    let mut camera_axis = Axis3i::Z;
+    let mut orbit = Orbit::default();
+
+    let mut previous_rules = game.rules().clone();
+    let mut rule_flash = 0.0;
+    let mut input_buffer = InputBuffer::new();
+    let mut next_tick = 0.0;
+    let mut show_trail = false;
+    let mut last_report: Option<StepReport> = None;
+    let mut show_report = false;
+    let mut segment_style = SegmentStyle::default();
 
     while !rl.window_should_close() {
         // Tick
@@ -72,36 +79,115 @@ fn main() {
             let input_right = rl.is_key_pressed(KeyboardKey::KEY_D) || rl.is_key_pressed(KeyboardKey::KEY_RIGHT);
             let input_up    = rl.is_key_pressed(KeyboardKey::KEY_W) || rl.is_key_pressed(KeyboardKey::KEY_UP);
             let input_down  = rl.is_key_pressed(KeyboardKey::KEY_S) || rl.is_key_pressed(KeyboardKey::KEY_DOWN);
-            let input_horizontal = (input_right as isize) - (input_left as isize);
-            let input_vertical   = (input_up    as isize) - (input_down as isize);
-            // horizontal takes priority
-            match (input_horizontal, input_vertical) {
-                (    1..,   _  ) => Some(Direction3::East),
-                (..=-1  ,   _  ) => Some(Direction3::West),
-                (    0,     1..) => Some(Direction3::North),
-                (    0, ..=-1  ) => Some(Direction3::South),
-                (    0,     0  ) => None,
-            }
+            let input_rise  = rl.is_key_pressed(KeyboardKey::KEY_E);
+            let input_sink  = rl.is_key_pressed(KeyboardKey::KEY_Q);
+            Direction3::from_input(
+                (input_right as i32) - (input_left as i32),
+                (input_up    as i32) - (input_down as i32),
+                (input_rise  as i32) - (input_sink as i32),
+            )
         };
 
+        let now = rl.get_time();
         if let Some(direction) = crawl_direction {
-            worm.crawl(direction);
+            input_buffer.press(direction, now);
+        } else if rl.is_key_pressed(KeyboardKey::KEY_Z) {
+            game.undo();
+        }
+
+        if now >= next_tick {
+            if let Some(direction) = input_buffer.take(now) {
+                last_report = Some(game.step(direction));
+                next_tick = now + TICK_INTERVAL;
+            }
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_T) {
+            show_trail = !show_trail;
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_C) {
+            segment_style = segment_style.toggled();
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_F1) {
+            show_report = !show_report;
+        }
+
+        for (key, axis) in [
+            (KeyboardKey::KEY_ONE,   Axis3i::X),
+            (KeyboardKey::KEY_TWO,   Axis3i::Y),
+            (KeyboardKey::KEY_THREE, Axis3i::Z),
+        ] {
+            if rl.is_key_pressed(key) {
+                camera_axis = axis;
+                orbit = Orbit::default();
+            }
+        }
+
+        {
+            let turn = ORBIT_SPEED * rl.get_frame_time();
+            let held = |key| (rl.is_key_down(key) as i32 as f32) * turn;
+            orbit = orbit.turned(
+                held(KeyboardKey::KEY_L) - held(KeyboardKey::KEY_J),
+                held(KeyboardKey::KEY_I) - held(KeyboardKey::KEY_K),
+            );
         }
+        orbit_camera(&mut camera, camera_axis, orbit, camera_target, camera_distance);
+
+        if !game.rules().diff(&previous_rules).is_empty() {
+            rule_flash = 0.25;
+            previous_rules = game.rules().clone();
+        }
+        rule_flash = (rule_flash - rl.get_frame_time()).max(0.0);
 
         // Draw
 
+        // How far through the current tick the animation is
+        let alpha = ((now - (next_tick - TICK_INTERVAL)) / TICK_INTERVAL).clamp(0.0, 1.0) as f32;
+
         {
-            let worm = &worm; // Immutable while drawing
+            let level = game.level(); // Immutable while drawing
             let mut d = rl.begin_drawing(&thread);
-            d.clear_background(Color::BLACK);
+            d.clear_background(if rule_flash > 0.0 { Color::DARKGRAY } else { Color::BLACK });
             {
                 let mut d3 = d.begin_mode3D(camera);
-                let tail_index = worm.num_segments() - 1;
-                for (i, segment) in worm.segment_positions().enumerate() {
-                    let world_pos = cell_to_world(segment);
-                    let growth = (((i == 0) as isize) - ((i == tail_index) as isize)) as f32;
-                    d3.draw_sphere(world_pos, CELL_SIZE / 2.0 + growth, Color::ORANGE);
+                draw_walls(&mut d3, &render_config, level);
+                for goal in level.goals() {
+                    d3.draw_cube_wires(render_config.cell_to_world(goal), render_config.cell_size, render_config.cell_size, render_config.cell_size, Color::GOLD);
+                }
+                for (cell, _text) in level.texts() {
+                    d3.draw_cube_v(render_config.cell_to_world(cell), render_config.voxel() * 0.8, Color::PINK);
                 }
+                if let Some(previous) = game.previous_level().filter(|_| show_trail) {
+                    for (_, worm) in previous.worms() {
+                        for world_pos in worm.segment_world_positions(&render_config) {
+                            draw_segment(&mut d3, &render_config, segment_style, world_pos, 0.0, Color::ORANGE.fade(0.2));
+                        }
+                    }
+                }
+                for (id, worm) in level.worms() {
+                    let directions: Vec<_> = worm.segment_directions().collect();
+                    let world_positions: Vec<_> = match game.previous_level().and_then(|previous| previous.worm(id)) {
+                        Some(previous) => interpolate_worm(previous, worm, alpha, &render_config),
+                        None => worm.segment_world_positions(&render_config).collect(),
+                    };
+                    // A worm that shrank is drawn with its lost segments until the tick ends, so the tail is the last of those
+                    let tail_index = world_positions.len() - 1;
+                    for (i, world_pos) in world_positions.into_iter().enumerate() {
+                        let growth = (((i == 0) as isize) - ((i == tail_index) as isize)) as f32;
+                        // The tail has no segment after it, so it continues the one before
+                        let color = directions.get(i).or(directions.last())
+                            .map_or(Color::ORANGE, |&direction| shade_segment(Color::ORANGE, direction, LIGHT_DIRECTION));
+                        draw_segment(&mut d3, &render_config, segment_style, world_pos, growth, color);
+                    }
+                }
+            }
+            if game.is_won() {
+                d.draw_text("WIN", 10, 10, 40, Color::GOLD);
+            }
+            if let Some(report) = last_report.as_ref().filter(|_| show_report) {
+                d.draw_text(&format_report(report), 10, 60, 20, Color::RAYWHITE);
             }
         }
     }
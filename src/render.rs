@@ -0,0 +1,229 @@
+//! Conversion between the game's grid and the rendered world.
+
+use raylib::prelude::*;
+use crate::{
+    spacial::{vector3i::Vector3i, direction3::Direction3, axis3i::Axis3i},
+    worm::Worm,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The largest coordinate magnitude [`RenderConfig::try_cell_to_world()`] accepts.
+/// Every integer up to this is exactly representable as an [`f32`]; past it, neighboring cells start to land on the same position.
+pub const MAX_EXACT_COORDINATE: i32 = 1 << f32::MANTISSA_DIGITS;
+
+/// The default sidelength of a cell in the game's grid.
+pub const CELL_SIZE: f32 = 8.0;
+
+/// Settings for how the grid is laid out in the world.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderConfig {
+    /// The sidelength of a cell in the game's grid.
+    pub cell_size: f32,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            cell_size: CELL_SIZE,
+        }
+    }
+}
+
+impl RenderConfig {
+    /// [`Self::cell_size`] as a [`Vector3`].
+    pub fn voxel(&self) -> Vector3 {
+        Vector3::new(self.cell_size, self.cell_size, self.cell_size)
+    }
+
+    /// Converts from grid coordinates to world coordinates.
+    pub fn cell_to_world(&self, cell: Vector3i) -> Vector3 {
+        Vector3::new(
+            cell.x as f32 * self.cell_size,
+            cell.y as f32 * self.cell_size,
+            cell.z as f32 * self.cell_size,
+        )
+    }
+
+    /// Like [`Self::cell_to_world()`], but [`None`] if any coordinate is beyond [`MAX_EXACT_COORDINATE`],
+    /// where converting to [`f32`] would silently misplace the cell.
+    pub fn try_cell_to_world(&self, cell: Vector3i) -> Option<Vector3> {
+        [cell.x, cell.y, cell.z]
+            .into_iter()
+            .all(|coordinate| coordinate.unsigned_abs() <= MAX_EXACT_COORDINATE as u32)
+            .then(|| self.cell_to_world(cell))
+    }
+
+    /// Converts from world coordinates to grid coordinates.
+    ///
+    /// Each cell spans half a [`Self::cell_size`] either side of its center.
+    /// A point exactly on the boundary between two cells belongs to the cell in the positive direction,
+    /// the same on both sides of the origin (e.g. both `-0.5` and `+0.5` cells round up, to `0` and `1`).
+    pub fn world_to_cell(&self, position: Vector3) -> Vector3i {
+        let to_cell = |coordinate: f32| (coordinate / self.cell_size + 0.5).floor() as i32;
+        Vector3i::new(
+            to_cell(position.x),
+            to_cell(position.y),
+            to_cell(position.z),
+        )
+    }
+
+    /// The corners of the face of `cell` pointing in `direction`,
+    /// wound counterclockwise as seen from outside the cell so raylib doesn't cull it.
+    pub fn face_quad(&self, cell: Vector3i, direction: Direction3) -> [Vector3; 4] {
+        use Direction3::*;
+        // Two edges along the face, such that `u` cross `v` points out of it
+        let (u, v) = match direction {
+            East  => (North, Up   ),
+            West  => (Up,    North),
+            North => (Up,    East ),
+            South => (East,  Up   ),
+            Up    => (East,  North),
+            Down  => (North, East ),
+        };
+        let half = self.cell_size / 2.0;
+        let to_world = |direction: Direction3| self.cell_to_world(direction.into()) / 2.0;
+        let center = self.cell_to_world(cell) + to_world(direction);
+        let (u, v) = (to_world(u), to_world(v));
+        debug_assert_eq!(u.cross(v).normalized() * half, to_world(direction));
+        [
+            center - u - v,
+            center + u - v,
+            center + u + v,
+            center - u + v,
+        ]
+    }
+}
+
+/// Move `camera` to look at `target` straight down `axis`, from `distance` away on the positive side.
+/// Up on screen is +Z when looking down X or Y, and +Y when looking down Z.
+pub fn look_down_axis(camera: &mut Camera3D, axis: Axis3i, target: Vector3, distance: f32) {
+    let (toward_camera, up) = match axis {
+        Axis3i::X => (Direction3::East,  Direction3::Up   ),
+        Axis3i::Y => (Direction3::North, Direction3::Up   ),
+        Axis3i::Z => (Direction3::Up,    Direction3::North),
+    };
+    camera.target = target;
+    camera.position = target + Vector3i::from(toward_camera).as_vector3() * distance;
+    camera.up = Vector3i::from(up).as_vector3();
+}
+
+/// How far the camera has swung around its target from looking straight down an axis, in radians.
+/// Snapping to another axis starts from straight down it again, so it resets the orbit too.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Orbit {
+    /// Turn around the view's up vector; positive swings the camera towards the right of the screen.
+    pub yaw: f32,
+    /// Tilt over the target; positive swings the camera towards the top of the screen.
+    /// Kept within [`Self::MAX_PITCH`] either way so the view never flips over.
+    pub pitch: f32,
+}
+
+impl Orbit {
+    /// The furthest [`Self::pitch`] goes either way, just short of looking along the view's up vector.
+    pub const MAX_PITCH: f32 = 1.5;
+
+    /// The orbit swung a further `yaw` and `pitch` radians, with the pitch clamped to [`Self::MAX_PITCH`].
+    pub fn turned(self, yaw: f32, pitch: f32) -> Self {
+        Self {
+            yaw: self.yaw + yaw,
+            pitch: (self.pitch + pitch).clamp(-Self::MAX_PITCH, Self::MAX_PITCH),
+        }
+    }
+}
+
+/// Move `camera` to look down `axis` as in [`look_down_axis()`], then swing it around `target` by `orbit`, keeping its `distance`.
+/// The default orbit leaves the camera straight down the axis.
+pub fn orbit_camera(camera: &mut Camera3D, axis: Axis3i, orbit: Orbit, target: Vector3, distance: f32) {
+    look_down_axis(camera, axis, target, distance);
+    let up = camera.up;
+    let back = (camera.position - target).normalized();
+    let right = up.cross(back);
+    let (sin_yaw, cos_yaw) = orbit.yaw.sin_cos();
+    let (sin_pitch, cos_pitch) = orbit.pitch.sin_cos();
+    // Level with the target, swung around by the yaw
+    let level = back * cos_yaw + right * sin_yaw;
+    camera.position = target + (level * cos_pitch + up * sin_pitch) * distance;
+    camera.up = up * cos_pitch - level * sin_pitch;
+}
+
+/// The direction light comes from in [`shade_segment()`]: above, and a little off to the side so flat worms aren't uniformly lit.
+pub const LIGHT_DIRECTION: Vector3 = Vector3::new(0.3, 0.5, 0.8);
+
+/// How bright a segment facing directly away from the light still is, from 0 to 1.
+const AMBIENT: f32 = 0.4;
+
+/// Darken `base` according to how directly a segment pointing in `segment_direction` faces `light`
+/// (the direction towards the light; it needn't be normalized).
+/// Alpha is left as-is.
+pub fn shade_segment(base: Color, segment_direction: Direction3, light: Vector3) -> Color {
+    let facing = Vector3i::from(segment_direction).as_vector3().dot(light.normalized());
+    // Half-Lambert, so segments facing away are dim rather than black
+    let brightness = AMBIENT + (1.0 - AMBIENT) * (facing * 0.5 + 0.5);
+    let scale = |channel: u8| (channel as f32 * brightness).round() as u8;
+    Color::new(scale(base.r), scale(base.g), scale(base.b), base.a)
+}
+
+/// The world position of each segment of `cur`, `alpha` of the way (0 to 1) from where it was in `prev`, for animating between ticks.
+///
+/// Segments are matched up head first. When the worms differ in length, the result is as long as the longer one:
+/// segments `cur` gained grow out of `prev`'s tail, and segments it lost shrink into `cur`'s tail.
+pub fn interpolate_worm(prev: &Worm, cur: &Worm, alpha: f32, config: &RenderConfig) -> Vec<Vector3> {
+    let prev: Vec<_> = prev.segment_world_positions(config).collect();
+    let cur: Vec<_> = cur.segment_world_positions(config).collect();
+    // Neither is empty, since every worm has a head
+    let at = |positions: &[Vector3], i: usize| *positions.get(i).unwrap_or(positions.last().unwrap());
+    (0..prev.len().max(cur.len()))
+        .map(|i| at(&prev, i).lerp(at(&cur, i), alpha))
+        .collect()
+}
+
+/// The color of a wall face pointing in `direction`.
+/// Faces are shaded as if lit from above, so the shape of wall masses is readable.
+pub fn wall_color(direction: Direction3) -> Color {
+    match direction {
+        Direction3::Up => Color::LIGHTGRAY,
+        Direction3::Down => Color::DARKGRAY,
+        _ => Color::GRAY,
+    }
+}
+
+/// Draws the faces of a level's walls that aren't hidden against other walls.
+pub fn draw_walls(d: &mut impl RaylibDraw3D, config: &RenderConfig, level: &crate::level::Level) {
+    for (cell, direction) in level.exposed_wall_faces() {
+        let [a, b, c, e] = config.face_quad(cell, direction);
+        let color = wall_color(direction);
+        d.draw_triangle3D(a, b, c, color);
+        d.draw_triangle3D(a, c, e, color);
+    }
+}
+
+/// The shape each worm segment is drawn as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SegmentStyle {
+    /// A ball filling the cell, which makes the worm look smooth.
+    #[default]
+    Sphere,
+    /// A cube filling the cell, which lines up with the grid.
+    Cube,
+}
+
+impl SegmentStyle {
+    /// The other style.
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Sphere => Self::Cube,
+            Self::Cube => Self::Sphere,
+        }
+    }
+}
+
+/// Draws one worm segment centered on `position`, a cell across plus `growth` (e.g. to make the head bigger and the tail smaller).
+pub fn draw_segment(d: &mut impl RaylibDraw3D, config: &RenderConfig, style: SegmentStyle, position: Vector3, growth: f32, color: Color) {
+    let size = config.cell_size + growth * 2.0;
+    match style {
+        SegmentStyle::Sphere => d.draw_sphere(position, size / 2.0, color),
+        SegmentStyle::Cube => d.draw_cube_v(position, Vector3::new(size, size, size), color),
+    }
+}
@@ -0,0 +1,253 @@
+use super::*;
+
+mod round_trip {
+    use super::*;
+
+    #[test]
+    fn test_scales() {
+        let cells = [
+            Vector3i::new(0, 0, 0),
+            Vector3i::new(1, 2, 3),
+            Vector3i::new(-5, 7, -11),
+            Vector3i::new(1000, -1000, 42),
+        ];
+        for cell_size in [1.0, 0.5, 8.0, 3.7, 100.0] {
+            let config = RenderConfig { cell_size };
+            for cell in cells {
+                assert_eq!(config.world_to_cell(config.cell_to_world(cell)), cell, "cell {cell:?} should round-trip at scale {cell_size}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_default() {
+        let config = RenderConfig::default();
+        assert_eq!(config.cell_size, CELL_SIZE);
+        assert_eq!(config.cell_to_world(Vector3i::new(1, 0, -1)), Vector3::new(CELL_SIZE, 0.0, -CELL_SIZE));
+    }
+}
+
+mod world_to_cell {
+    use super::*;
+
+    fn cell_x(config: &RenderConfig, x: f32) -> i32 {
+        config.world_to_cell(Vector3::new(x, 0.0, 0.0)).x
+    }
+
+    #[test]
+    fn test_half_cell() {
+        let config = RenderConfig::default();
+        assert_eq!(cell_x(&config, -0.5 * CELL_SIZE), 0, "boundary below origin should belong to the cell above it");
+        assert_eq!(cell_x(&config, 0.5 * CELL_SIZE), 1, "boundary above origin should belong to the cell above it");
+    }
+
+    #[test]
+    fn test_negative() {
+        let config = RenderConfig::default();
+        assert_eq!(cell_x(&config, -1.5 * CELL_SIZE), -1);
+        assert_eq!(cell_x(&config, -2.5 * CELL_SIZE), -2);
+        assert_eq!(cell_x(&config, -2.49 * CELL_SIZE), -2);
+        assert_eq!(cell_x(&config, -2.51 * CELL_SIZE), -3);
+        assert_eq!(cell_x(&config, -0.49 * CELL_SIZE), 0);
+    }
+
+    #[test]
+    fn test_symmetric() {
+        let config = RenderConfig::default();
+        for offset in [-0.5, -0.25, 0.0, 0.25, 0.49] {
+            let base = cell_x(&config, offset * CELL_SIZE);
+            for cell in -4..=4 {
+                let x = (cell as f32 + offset) * CELL_SIZE;
+                assert_eq!(cell_x(&config, x), base + cell, "offset {offset} should map the same way in cell {cell}");
+            }
+        }
+    }
+}
+
+mod face_quad {
+    use super::*;
+
+    #[test]
+    fn test_outward_winding() {
+        let config = RenderConfig::default();
+        let cell = Vector3i::new(1, -2, 3);
+        for direction in Direction3::ALL {
+            let [a, b, c, _] = config.face_quad(cell, direction);
+            let normal = (b - a).cross(c - a).normalized();
+            assert_eq!(normal, Vector3i::from(direction).as_vector3(), "{direction:?} face should be wound to face outward");
+        }
+    }
+
+    #[test]
+    fn test_on_face() {
+        let config = RenderConfig { cell_size: 2.0 };
+        let quad = config.face_quad(Vector3i::new(0, 0, 0), Direction3::Up);
+        for corner in quad {
+            assert_eq!(corner.z, 1.0, "top face should sit half a cell above the center");
+            assert_eq!(corner.x.abs(), 1.0);
+            assert_eq!(corner.y.abs(), 1.0);
+        }
+    }
+}
+
+mod shade_segment {
+    use super::*;
+
+    #[test]
+    fn test_facing_light() {
+        let light = Vector3::new(0.0, 0.0, 1.0);
+        assert_eq!(shade_segment(Color::ORANGE, Direction3::Up, light), Color::ORANGE, "facing the light should be full brightness");
+        let away = shade_segment(Color::ORANGE, Direction3::Down, light);
+        let side = shade_segment(Color::ORANGE, Direction3::East, light);
+        assert!(away.r < side.r && side.r < Color::ORANGE.r, "brightness should fall off away from the light");
+        assert!(away.r > 0, "segments facing away should still be visible");
+    }
+
+    #[test]
+    fn test_keeps_alpha() {
+        let base = Color::new(200, 100, 50, 128);
+        assert_eq!(shade_segment(base, Direction3::West, LIGHT_DIRECTION).a, 128);
+    }
+
+    #[test]
+    fn test_unnormalized_light() {
+        assert_eq!(
+            shade_segment(Color::ORANGE, Direction3::North, Vector3::new(0.0, 5.0, 5.0)),
+            shade_segment(Color::ORANGE, Direction3::North, Vector3::new(0.0, 1.0, 1.0)),
+        );
+    }
+}
+
+mod look_down_axis {
+    use super::*;
+
+    fn camera() -> Camera3D {
+        Camera3D::perspective(Vector3::zero(), Vector3::zero(), Vector3::zero(), 90.0)
+    }
+
+    #[test]
+    fn test_each_axis() {
+        let target = Vector3::new(1.0, 2.0, 3.0);
+        for (axis, offset, up) in [
+            (Axis3i::X, Vector3::new(10.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0)),
+            (Axis3i::Y, Vector3::new(0.0, 10.0, 0.0), Vector3::new(0.0, 0.0, 1.0)),
+            (Axis3i::Z, Vector3::new(0.0, 0.0, 10.0), Vector3::new(0.0, 1.0, 0.0)),
+        ] {
+            let mut camera = camera();
+            look_down_axis(&mut camera, axis, target, 10.0);
+            assert_eq!(camera.target, target, "{axis:?}");
+            assert_eq!(camera.position, target + offset, "{axis:?}");
+            assert_eq!(camera.up, up, "{axis:?}");
+            assert_eq!(camera.up.dot(camera.position - camera.target), 0.0, "up should be perpendicular to the view for {axis:?}");
+        }
+    }
+}
+
+mod orbit_camera {
+    use super::*;
+
+    fn camera() -> Camera3D {
+        Camera3D::perspective(Vector3::zero(), Vector3::zero(), Vector3::zero(), 90.0)
+    }
+
+    fn assert_near(actual: Vector3, expected: Vector3, message: &str) {
+        assert!(actual.distance_to(expected) < 1e-4, "{message}: expected {expected:?}, got {actual:?}");
+    }
+
+    #[test]
+    fn test_default_is_snapped() {
+        let target = Vector3::new(1.0, 2.0, 3.0);
+        for axis in [Axis3i::X, Axis3i::Y, Axis3i::Z] {
+            let (mut snapped, mut orbited) = (camera(), camera());
+            look_down_axis(&mut snapped, axis, target, 10.0);
+            orbit_camera(&mut orbited, axis, Orbit::default(), target, 10.0);
+            assert_near(orbited.position, snapped.position, &format!("{axis:?} position"));
+            assert_near(orbited.up, snapped.up, &format!("{axis:?} up"));
+        }
+    }
+
+    #[test]
+    fn test_quarter_turns() {
+        let target = Vector3::new(1.0, 2.0, 3.0);
+        let quarter = std::f32::consts::FRAC_PI_2;
+        let mut camera = camera();
+        orbit_camera(&mut camera, Axis3i::Z, Orbit { yaw: quarter, pitch: 0.0 }, target, 10.0);
+        assert_near(camera.position, target + Vector3::new(10.0, 0.0, 0.0), "yaw should swing the camera to the right");
+        assert_near(camera.up, Vector3::new(0.0, 1.0, 0.0), "yaw should keep the up vector");
+
+        orbit_camera(&mut camera, Axis3i::Z, Orbit { yaw: 0.0, pitch: 1.0 }, target, 10.0);
+        assert_near(camera.target, target, "the camera should keep looking at the target");
+        assert!((camera.position.distance_to(target) - 10.0).abs() < 1e-4, "the camera should keep its distance");
+        assert!(camera.position.y > target.y, "pitch should swing the camera towards the top of the screen");
+        assert!(camera.up.dot(camera.position - camera.target).abs() < 1e-4, "up should stay perpendicular to the view");
+    }
+
+    #[test]
+    fn test_pitch_clamped() {
+        let orbit = Orbit::default().turned(0.5, 10.0).turned(0.5, 0.0);
+        assert_eq!(orbit, Orbit { yaw: 1.0, pitch: Orbit::MAX_PITCH });
+        assert_eq!(orbit.turned(0.0, -20.0).pitch, -Orbit::MAX_PITCH);
+    }
+}
+
+mod interpolate_worm {
+    use super::*;
+
+    #[test]
+    fn test_endpoints() {
+        let config = RenderConfig::default();
+        let prev = Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap();
+        let mut cur = prev.clone();
+        cur.crawl(Direction3::North);
+        assert!(interpolate_worm(&prev, &cur, 0.0, &config).into_iter().eq(prev.segment_world_positions(&config)));
+        assert!(interpolate_worm(&prev, &cur, 1.0, &config).into_iter().eq(cur.segment_world_positions(&config)));
+        assert_eq!(interpolate_worm(&prev, &cur, 0.5, &config)[0], config.cell_to_world(Vector3i::new(1, 0, 0)).lerp(config.cell_to_world(Vector3i::new(1, 1, 0)), 0.5));
+    }
+
+    #[test]
+    fn test_grown() {
+        let config = RenderConfig::default();
+        let prev = Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap();
+        let mut cur = prev.clone();
+        cur.grow_by(1);
+        let start = interpolate_worm(&prev, &cur, 0.0, &config);
+        assert_eq!(start.len(), 3);
+        assert_eq!(start[2], config.cell_to_world(Vector3i::new(0, 0, 0)), "the new segment should start at the old tail");
+        assert!(interpolate_worm(&prev, &cur, 1.0, &config).into_iter().eq(cur.segment_world_positions(&config)));
+    }
+
+    #[test]
+    fn test_shrunk() {
+        let config = RenderConfig::default();
+        let prev = Worm::from_str(Vector3i::new(2, 0, 0), "<<").unwrap();
+        let mut cur = prev.clone();
+        cur.shrink_by(1);
+        let end = interpolate_worm(&prev, &cur, 1.0, &config);
+        assert_eq!(end.len(), 3);
+        assert_eq!(end[2], config.cell_to_world(Vector3i::new(1, 0, 0)), "the lost segment should end at the new tail");
+    }
+}
+
+mod try_cell_to_world {
+    use super::*;
+
+    #[test]
+    fn test_limit() {
+        let config = RenderConfig { cell_size: 1.0 };
+        let inside = Vector3i::new(MAX_EXACT_COORDINATE, -MAX_EXACT_COORDINATE, 0);
+        assert_eq!(config.try_cell_to_world(inside), Some(config.cell_to_world(inside)));
+        assert_eq!(config.try_cell_to_world(Vector3i::new(0, MAX_EXACT_COORDINATE + 1, 0)), None);
+        assert_eq!(config.try_cell_to_world(Vector3i::new(0, 0, -MAX_EXACT_COORDINATE - 1)), None);
+    }
+}
+
+mod segment_style {
+    use super::*;
+
+    #[test]
+    fn test_toggled() {
+        assert_eq!(SegmentStyle::default(), SegmentStyle::Sphere);
+        assert_eq!(SegmentStyle::Sphere.toggled(), SegmentStyle::Cube);
+        assert_eq!(SegmentStyle::Sphere.toggled().toggled(), SegmentStyle::Sphere);
+    }
+}
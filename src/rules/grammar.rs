@@ -0,0 +1,76 @@
+//! How operators bind when reading a [`super::statement::Statement`].
+//!
+//! | Operator      | Precedence | Associativity | Meaning                                                  |
+//! |---------------|-----------:|---------------|----------------------------------------------------------|
+//! | `NOT`         | 4 (tightest) | Prefix      | Negates only the single word right after it.             |
+//! | `AND`         | 3          | Associative   | Joins any number of terms on the same side.              |
+//! | `ON`          | 2          | None          | Conditions the subjects before it on the nouns after it. |
+//! | `IS`, `HAS`   | 1 (loosest)| None          | Separates the subjects from the predicates.              |
+//!
+//! Reading from loosest to tightest:
+//! `NOT WORM AND WALL ON GOAL IS YOU AND NOT WIN` is
+//! `((NOT WORM) AND WALL) ON GOAL` `IS` `YOU AND (NOT WIN)`.
+//!
+//! Non-associative operators may appear at most once per statement.
+//! `AND` terms on the conditional side of `ON` must all hold at once, while
+//! `AND` terms among the subjects or predicates distribute into separate rules.
+
+use std::fmt;
+use super::word::{operator::Operator, Word};
+
+/// How repeated uses of an operator group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// Applies to the single word after it.
+    Prefix,
+    /// `a x b x c` is the same regardless of grouping.
+    Associative,
+    /// `a x b x c` is not grammatical.
+    None,
+}
+
+impl Operator {
+    /// How tightly the operator binds; higher binds tighter. See the [module docs](self).
+    pub fn precedence(self) -> u8 {
+        match self {
+            Self::NOT => 4,
+            Self::AND => 3,
+            Self::ON  => 2,
+            Self::IS | Self::HAS => 1,
+        }
+    }
+
+    /// How repeated uses of the operator group. See the [module docs](self).
+    pub fn associativity(self) -> Associativity {
+        match self {
+            Self::NOT => Associativity::Prefix,
+            Self::AND => Associativity::Associative,
+            Self::ON | Self::IS | Self::HAS => Associativity::None,
+        }
+    }
+}
+
+/// The reason a sequence of words is not a valid statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrammarError {
+    /// The word at `position` can't go there.
+    UnexpectedWord {
+        /// Index of the word in the statement.
+        position: usize,
+        /// The word.
+        word: Word,
+    },
+    /// The statement stopped before it was complete.
+    UnexpectedEnd,
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedWord { position, word } => write!(f, "unexpected {word} at word {position}"),
+            Self::UnexpectedEnd => write!(f, "statement ends before it is complete"),
+        }
+    }
+}
+
+impl std::error::Error for GrammarError {}
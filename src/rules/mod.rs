@@ -3,3 +3,5 @@
 pub mod word;
 pub mod text;
 pub mod statement;
+pub mod ruleset;
+pub mod grammar;
@@ -0,0 +1,215 @@
+//! The rules in effect.
+
+use std::{collections::{HashSet, BTreeMap}, fmt, hash::{Hash, Hasher}};
+use crate::{spacial::vector3i::Vector3i, level::Level};
+use super::{
+    statement::Statement,
+    word::{Word, noun::Noun, operator::Operator, property::Property},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The set of properties each noun currently participates in, the nouns each turns into,
+/// and the properties each participates in only while on another noun.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ruleset {
+    properties: HashSet<(Noun, Property)>,
+    transformations: HashSet<(Noun, Noun)>,
+    conditionals: HashSet<(Noun, Noun, Property)>,
+}
+
+impl Ruleset {
+    /// Construct a ruleset with no rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `noun` participate in `property`. i.e. `[Noun(noun), IS, Property(property)]`
+    pub fn insert(&mut self, noun: Noun, property: Property) {
+        self.properties.insert((noun, property));
+    }
+
+    /// Stop `noun` from participating in `property`.
+    pub fn remove(&mut self, noun: Noun, property: Property) {
+        self.properties.remove(&(noun, property));
+    }
+
+    /// `noun` participates in `property`?
+    pub fn has(&self, noun: Noun, property: Property) -> bool {
+        self.properties.contains(&(noun, property))
+    }
+
+    /// Make every `from` turn into `into`. i.e. `[Noun(from), IS, Noun(into)]`
+    pub fn insert_transformation(&mut self, from: Noun, into: Noun) {
+        self.transformations.insert((from, into));
+    }
+
+    /// `from` turns into `into`?
+    pub fn transforms(&self, from: Noun, into: Noun) -> bool {
+        self.transformations.contains(&(from, into))
+    }
+
+    /// Make `noun` participate in `property` wherever it shares a cell with `on`. i.e. `[Noun(noun), ON, Noun(on), IS, Property(property)]`
+    pub fn insert_conditional(&mut self, noun: Noun, on: Noun, property: Property) {
+        self.conditionals.insert((noun, on, property));
+    }
+
+    /// Every property an object of `noun` in `cell` of `level` participates in:
+    /// its unconditional properties, plus those from `[noun, ON, on, IS, property]` rules where some object of `on` is also in the cell.
+    pub fn properties_of_at(&self, noun: Noun, cell: Vector3i, level: &Level) -> HashSet<Property> {
+        let mut properties: HashSet<Property> = self.properties
+            .iter()
+            .filter(|&&(n, _)| n == noun)
+            .map(|&(_, property)| property)
+            .collect();
+        if self.conditionals.iter().any(|&(n, _, _)| n == noun) {
+            let here: HashSet<Noun> = level.objects_at(cell).iter().map(|object| object.noun()).collect();
+            properties.extend(self.conditionals
+                .iter()
+                .filter(|&&(n, on, _)| n == noun && here.contains(&on))
+                .map(|&(_, _, property)| property)
+            );
+        }
+        properties
+    }
+
+    /// Some `[noun, ON, _, IS, property]` rule exists, so `noun` may participate in `property` in some cells?
+    pub fn has_conditional(&self, noun: Noun, property: Property) -> bool {
+        self.conditionals.iter().any(|&(n, _, p)| (n, p) == (noun, property))
+    }
+
+    /// Every `(noun, on, property)` conditional rule, sorted by noun, then `on`, then property.
+    pub fn conditionals(&self) -> Vec<(Noun, Noun, Property)> {
+        let mut conditionals: Vec<_> = self.conditionals.iter().copied().collect();
+        conditionals.sort();
+        conditionals
+    }
+
+    /// Every `(noun, property)` rule, sorted by noun then property.
+    pub fn properties(&self) -> Vec<(Noun, Property)> {
+        let mut properties: Vec<_> = self.properties.iter().copied().collect();
+        properties.sort();
+        properties
+    }
+
+    /// Every `(from, into)` transformation, sorted by `from` then `into`.
+    pub fn transformations(&self) -> Vec<(Noun, Noun)> {
+        let mut transformations: Vec<_> = self.transformations.iter().copied().collect();
+        transformations.sort();
+        transformations
+    }
+
+    /// The rules written as few statements as possible, for listing on screen, e.g. `WORM AND WALL IS YOU AND PUSH`.
+    ///
+    /// Nouns with exactly the same properties and transformations share a statement, joined with `AND`,
+    /// so [splitting](Statement::split()) every statement gives back exactly these rules.
+    /// Statements are ordered by their first noun, followed by one statement per [conditional](Self::conditionals()) rule.
+    pub fn compact_statements(&self) -> Vec<Statement> {
+        let mut predicates: BTreeMap<Noun, Vec<Word>> = BTreeMap::new();
+        for (noun, property) in self.properties() {
+            predicates.entry(noun).or_default().push(Word::Property(property));
+        }
+        for (from, into) in self.transformations() {
+            predicates.entry(from).or_default().push(Word::Noun(into));
+        }
+
+        let mut groups: Vec<(Vec<Noun>, Vec<Word>)> = Vec::new();
+        for (noun, predicates) in predicates {
+            match groups.iter_mut().find(|(_, group_predicates)| *group_predicates == predicates) {
+                Some((subjects, _)) => subjects.push(noun),
+                None => groups.push((vec![noun], predicates)),
+            }
+        }
+
+        let and = |words: Vec<Word>| words
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, word)| (i != 0).then_some(Word::Operator(Operator::AND)).into_iter().chain([word]));
+        groups
+            .into_iter()
+            .map(|(subjects, predicates)| and(subjects.into_iter().map(Word::Noun).collect())
+                .chain([Word::Operator(Operator::IS)])
+                .chain(and(predicates))
+                .collect()
+            )
+            .chain(self.conditionals()
+                .into_iter()
+                .map(|(noun, on, property)| Statement::from_iter([
+                    Word::Noun(noun),
+                    Word::Operator(Operator::ON),
+                    Word::Noun(on),
+                    Word::Operator(Operator::IS),
+                    Word::Property(property),
+                ]))
+            )
+            .collect()
+    }
+
+    /// Objects of the two nouns can collide, push, or otherwise affect each other while sharing a cell?
+    /// They can only if both or neither are [`Property::FLOAT`].
+    pub fn interacts(&self, a: Noun, b: Noun) -> bool {
+        self.has(a, Property::FLOAT) == self.has(b, Property::FLOAT)
+    }
+
+    /// What changed going from `previous` to `self`.
+    pub fn diff(&self, previous: &Ruleset) -> RuleDiff {
+        let mut gained: Vec<_> = self.properties.difference(&previous.properties).copied().collect();
+        let mut lost: Vec<_> = previous.properties.difference(&self.properties).copied().collect();
+        gained.sort();
+        lost.sort();
+        RuleDiff { gained, lost }
+    }
+}
+
+/// Hashes the rules in sorted order, so equal rulesets hash the same regardless of insertion order.
+impl Hash for Ruleset {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.properties().hash(state);
+        self.transformations().hash(state);
+        self.conditionals().hash(state);
+    }
+}
+
+/// One atomic rule per line, e.g. `WORM IS YOU`:
+/// properties sorted by noun then property, followed by [transformations](Ruleset::transformations()) and [conditionals](Ruleset::conditionals()).
+impl fmt::Display for Ruleset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (noun, property) in self.properties() {
+            writeln!(f, "{noun} IS {property}")?;
+        }
+        for (from, into) in self.transformations() {
+            writeln!(f, "{from} IS {into}")?;
+        }
+        for (noun, on, property) in self.conditionals() {
+            writeln!(f, "{noun} ON {on} IS {property}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The rules that changed between two [`Ruleset`]s, each sorted by noun then property.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleDiff {
+    /// Rules that are newly in effect.
+    pub gained: Vec<(Noun, Property)>,
+    /// Rules that are no longer in effect.
+    pub lost: Vec<(Noun, Property)>,
+}
+
+impl RuleDiff {
+    /// Nothing changed?
+    pub fn is_empty(&self) -> bool {
+        self.gained.is_empty() && self.lost.is_empty()
+    }
+}
+
+impl FromIterator<(Noun, Property)> for Ruleset {
+    fn from_iter<T: IntoIterator<Item = (Noun, Property)>>(iter: T) -> Self {
+        Self {
+            properties: iter.into_iter().collect(),
+            transformations: HashSet::new(),
+            conditionals: HashSet::new(),
+        }
+    }
+}
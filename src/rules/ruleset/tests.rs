@@ -0,0 +1,121 @@
+use super::*;
+
+mod diff {
+    use super::*;
+
+    #[test]
+    fn test_gained() {
+        let previous = Ruleset::new();
+        let current = Ruleset::from_iter([(Noun::WORM, Property::YOU)]);
+        let diff = current.diff(&previous);
+        assert_eq!(diff.gained, [(Noun::WORM, Property::YOU)]);
+        assert!(diff.lost.is_empty());
+    }
+
+    #[test]
+    fn test_lost() {
+        let previous = Ruleset::from_iter([(Noun::WORM, Property::YOU), (Noun::GOAL, Property::WIN)]);
+        let current = Ruleset::from_iter([(Noun::GOAL, Property::WIN)]);
+        let diff = current.diff(&previous);
+        assert!(diff.gained.is_empty());
+        assert_eq!(diff.lost, [(Noun::WORM, Property::YOU)]);
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::YOU)]);
+        assert!(rules.diff(&rules.clone()).is_empty());
+    }
+}
+
+mod interacts {
+    use super::*;
+
+    #[test]
+    fn test_layers() {
+        let rules = Ruleset::from_iter([(Noun::WORM, Property::FLOAT), (Noun::TEXT, Property::FLOAT)]);
+        assert!(rules.interacts(Noun::WORM, Noun::TEXT), "both float");
+        assert!(rules.interacts(Noun::WALL, Noun::GOAL), "neither floats");
+        assert!(!rules.interacts(Noun::WORM, Noun::WALL));
+        assert!(!rules.interacts(Noun::WALL, Noun::WORM), "interaction is symmetric");
+    }
+}
+
+mod display {
+    use super::*;
+
+    #[test]
+    fn test_sorted() {
+        let mut rules = Ruleset::from_iter([(Noun::GOAL, Property::WIN), (Noun::WORM, Property::PUSH), (Noun::WORM, Property::YOU)]);
+        rules.insert_transformation(Noun::WALL, Noun::WORM);
+        assert_eq!(rules.to_string(), "WORM IS YOU\nWORM IS PUSH\nGOAL IS WIN\nWALL IS WORM\n");
+        assert_eq!(Ruleset::new().to_string(), "");
+    }
+}
+
+mod compact_statements {
+    use super::*;
+
+    fn statement(text: &str) -> Statement {
+        text.split_whitespace().map(|word| word.parse().unwrap()).collect()
+    }
+
+    fn rules_of(statements: &[Statement]) -> Ruleset {
+        let mut rules = Ruleset::new();
+        for statement in statements.iter().flat_map(|statement| statement.split().unwrap()) {
+            if let Some((noun, property)) = statement.as_rule() {
+                rules.insert(noun, property);
+            } else if let Some((from, into)) = statement.as_transformation() {
+                rules.insert_transformation(from, into);
+            }
+        }
+        rules
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rules = rules_of(&[
+            statement("WORM AND WALL IS YOU AND PUSH AND GOAL"),
+            statement("GOAL IS WIN"),
+            statement("TEXT IS PUSH AND YOU"),
+        ]);
+        let compact = rules.compact_statements();
+        assert_eq!(rules_of(&compact), rules);
+        assert_eq!(compact.len(), 3, "nouns with the same rules should share a statement");
+    }
+
+    #[test]
+    fn test_output() {
+        let mut rules = Ruleset::from_iter([(Noun::WORM, Property::YOU), (Noun::WALL, Property::YOU), (Noun::GOAL, Property::WIN)]);
+        rules.insert_transformation(Noun::TEXT, Noun::WALL);
+        let compact: Vec<_> = rules.compact_statements().iter().map(Statement::to_string).collect();
+        assert_eq!(compact, ["WORM AND WALL IS YOU", "GOAL IS WIN", "TEXT IS WALL"]);
+        assert!(Ruleset::new().compact_statements().is_empty());
+    }
+}
+
+mod properties_of_at {
+    use super::*;
+    use crate::{level::Level, worm::Worm};
+
+    #[test]
+    fn test_on_goal() {
+        let mut level = Level::new(Vector3i::new(4, 1, 1));
+        level.add_goal(Vector3i::new(2, 0, 0));
+        level.add_worm(Worm::from_str(Vector3i::new(2, 0, 0), "<").unwrap());
+        let mut rules = Ruleset::from_iter([(Noun::WORM, Property::PUSH)]);
+        rules.insert_conditional(Noun::WORM, Noun::GOAL, Property::YOU);
+        assert_eq!(rules.properties_of_at(Noun::WORM, Vector3i::new(2, 0, 0), &level), HashSet::from([Property::PUSH, Property::YOU]));
+        assert_eq!(rules.properties_of_at(Noun::WORM, Vector3i::new(1, 0, 0), &level), HashSet::from([Property::PUSH]), "off the goal");
+        assert!(rules.has_conditional(Noun::WORM, Property::YOU));
+        assert!(!rules.has(Noun::WORM, Property::YOU), "conditional rules don't hold everywhere");
+    }
+
+    #[test]
+    fn test_display_and_compact() {
+        let mut rules = Ruleset::new();
+        rules.insert_conditional(Noun::WORM, Noun::GOAL, Property::WIN);
+        assert_eq!(rules.to_string(), "WORM ON GOAL IS WIN\n");
+        assert_eq!(rules.compact_statements().iter().map(Statement::to_string).collect::<Vec<_>>(), ["WORM ON GOAL IS WIN"]);
+    }
+}
@@ -1,12 +1,17 @@
+//! Rules written out as words.
+
+use std::fmt;
+use super::grammar::GrammarError;
 use super::word::Word;
 use super::word::{
-    noun::Noun::*,
+    noun,
+    property,
     operator::Operator::*,
-    property::Property::*,
     Word::*,
 };
 
-/// A sequence of words forming
+/// A sequence of words forming a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Statement(Vec<Word>);
 
 impl FromIterator<Word> for Statement {
@@ -15,26 +20,277 @@ impl FromIterator<Word> for Statement {
     }
 }
 
-// impl Statement {
-//     // Break a statement apart using AND as a delimiter
-//     pub fn split(&self) -> impl Iterator<Item = impl Iterator<Item = Statement>> {
-//         // [a AND b IS x AND y] => [[a IS x], [a IS Y], [b IS x], [b IS y]]
-//         self.0.iter()
-//             .as_slice()
-//             .split(|word| word == &Operator(AND))
-//     }
-// }
-
-// #[cfg(test)]
-// mod test {
-//     use super::*;
-
-//     #[test]
-//     fn test_split() {
-//         let statement = Statement::from_iter([
-//             Noun(WORM),
-//             Operator(IS),
-//             Property(YOU),
-//         ]);
-//     }
-// }
+/// The words separated by spaces, e.g. `WORM IS YOU`.
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, word) in self.0.iter().enumerate() {
+            if i != 0 {
+                f.write_str(" ")?;
+            }
+            word.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a Statement {
+    type Item = &'a Word;
+    type IntoIter = std::slice::Iter<'a, Word>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Split `words` into `AND`-separated terms, each any number of `NOT`s followed by one word accepted by `is_term`.
+/// `offset` is the index of `words[0]` in the statement and `next` is the word after `words`, for error reporting.
+fn terms(
+    words: &[Word],
+    offset: usize,
+    next: Option<Word>,
+    is_term: impl Fn(Word) -> bool,
+) -> Result<Vec<&[Word]>, GrammarError> {
+    let mut terms = Vec::new();
+    let mut term_start = Some(0);
+    for (i, &word) in words.iter().enumerate() {
+        match term_start {
+            Some(_) if word == Operator(NOT) => {},
+            Some(start) if is_term(word) => {
+                terms.push(&words[start..=i]);
+                term_start = None;
+            },
+            None if word == Operator(AND) => term_start = Some(i + 1),
+            _ => return Err(GrammarError::UnexpectedWord { position: offset + i, word }),
+        }
+    }
+    if term_start.is_some() {
+        return Err(match next {
+            Some(word) => GrammarError::UnexpectedWord { position: offset + words.len(), word },
+            None => GrammarError::UnexpectedEnd,
+        });
+    }
+    Ok(terms)
+}
+
+impl Statement {
+    /// The words in the order they are read.
+    pub fn words(&self) -> &[Word] {
+        &self.0
+    }
+
+    /// The rule this statement grants, if it is a single `[noun IS property]`.
+    /// Anything else, including statements that still need [`Self::split()`], gives [`None`].
+    pub fn as_rule(&self) -> Option<(noun::Noun, property::Property)> {
+        match self.0[..] {
+            [Noun(noun), Operator(IS), Property(property)] => Some((noun, property)),
+            _ => None,
+        }
+    }
+
+    /// The rule this statement takes away, if it is a single `[noun IS NOT property]`.
+    /// Like [`Self::as_rule()`], anything else gives [`None`].
+    pub fn as_negated_rule(&self) -> Option<(noun::Noun, property::Property)> {
+        match self.0[..] {
+            [Noun(noun), Operator(IS), Operator(NOT), Property(property)] => Some((noun, property)),
+            _ => None,
+        }
+    }
+
+    /// The transformation this statement causes, if it is a single `[noun IS noun]`.
+    /// Like [`Self::as_rule()`], anything else gives [`None`].
+    pub fn as_transformation(&self) -> Option<(noun::Noun, noun::Noun)> {
+        match self.0[..] {
+            [Noun(from), Operator(IS), Noun(into)] => Some((from, into)),
+            _ => None,
+        }
+    }
+
+    /// The conditional rule this statement grants, if it is a single `[noun ON on IS property]`, as `(noun, on, property)`.
+    /// Like [`Self::as_rule()`], anything else gives [`None`].
+    pub fn as_conditional_rule(&self) -> Option<(noun::Noun, noun::Noun, property::Property)> {
+        match self.0[..] {
+            [Noun(noun), Operator(ON), Noun(on), Operator(IS), Property(property)] => Some((noun, on, property)),
+            _ => None,
+        }
+    }
+
+    /// Break a statement apart into atomic rules, distributing `AND` as described in [`super::grammar`].
+    ///
+    /// Example:
+    /// ```text
+    /// [a AND b IS x AND y] => [[a IS x], [a IS y], [b IS x], [b IS y]]
+    /// ```
+    pub fn split(&self) -> Result<Vec<Statement>, GrammarError> {
+        let words = &self.0;
+
+        let mut verbs = words
+            .iter()
+            .enumerate()
+            .filter(|(_, &word)| matches!(word, Operator(IS | HAS)));
+        let Some((verb_index, &verb)) = verbs.next() else {
+            return Err(GrammarError::UnexpectedEnd);
+        };
+        if let Some((position, &word)) = verbs.next() {
+            return Err(GrammarError::UnexpectedWord { position, word });
+        }
+
+        let (subject_side, predicate_side) = (&words[..verb_index], &words[verb_index + 1..]);
+
+        let mut ons = subject_side
+            .iter()
+            .enumerate()
+            .filter(|(_, &word)| word == Operator(ON));
+        let on_index = ons.next().map(|(i, _)| i);
+        if let Some((position, &word)) = ons.next() {
+            return Err(GrammarError::UnexpectedWord { position, word });
+        }
+
+        let (subjects, conditions) = match on_index {
+            Some(on_index) => (
+                terms(&subject_side[..on_index], 0, Some(Operator(ON)), Word::is_noun)?,
+                &subject_side[on_index..],
+            ),
+            None => (terms(subject_side, 0, Some(verb), Word::is_noun)?, &[][..]),
+        };
+        if let Some(on_index) = on_index {
+            terms(&conditions[1..], on_index + 1, Some(verb), Word::is_noun)?;
+        }
+
+        let predicates = match verb {
+            Operator(IS) => terms(predicate_side, verb_index + 1, None, |word| word.is_noun() || word.is_property())?,
+            _ => terms(predicate_side, verb_index + 1, None, Word::is_noun)?,
+        };
+
+        Ok(subjects
+            .iter()
+            .flat_map(|subject| predicates
+                .iter()
+                .map(move |predicate| subject
+                    .iter()
+                    .chain(conditions)
+                    .chain([&verb])
+                    .chain(predicate.iter())
+                    .copied()
+                    .collect()
+                )
+            )
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::word::{noun::Noun::*, property::Property::*};
+
+    fn statement(text: &str) -> Statement {
+        text.split_whitespace()
+            .map(|word| word.parse().unwrap())
+            .collect()
+    }
+
+    fn split(text: &str) -> Result<Vec<Statement>, GrammarError> {
+        statement(text).split()
+    }
+
+    #[test]
+    fn test_atomic() {
+        assert_eq!(split("WORM IS YOU"), Ok(vec![statement("WORM IS YOU")]));
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(split("WORM AND WALL IS YOU AND WIN"), Ok(vec![
+            statement("WORM IS YOU"),
+            statement("WORM IS WIN"),
+            statement("WALL IS YOU"),
+            statement("WALL IS WIN"),
+        ]));
+    }
+
+    #[test]
+    fn test_not_binds_tightest() {
+        assert_eq!(split("WORM IS NOT YOU AND WIN"), Ok(vec![
+            statement("WORM IS NOT YOU"),
+            statement("WORM IS WIN"),
+        ]));
+        assert_eq!(split("NOT WORM AND WALL IS YOU"), Ok(vec![
+            statement("NOT WORM IS YOU"),
+            statement("WALL IS YOU"),
+        ]));
+    }
+
+    #[test]
+    fn test_on_binds_looser_than_and() {
+        assert_eq!(split("WORM AND WALL ON GOAL IS YOU"), Ok(vec![
+            statement("WORM ON GOAL IS YOU"),
+            statement("WALL ON GOAL IS YOU"),
+        ]));
+        assert_eq!(split("WORM ON GOAL AND WALL IS YOU AND WIN"), Ok(vec![
+            statement("WORM ON GOAL AND WALL IS YOU"),
+            statement("WORM ON GOAL AND WALL IS WIN"),
+        ]), "conditions should stay together");
+    }
+
+    #[test]
+    fn test_has() {
+        assert_eq!(split("WORM HAS GOAL AND WALL"), Ok(vec![
+            statement("WORM HAS GOAL"),
+            statement("WORM HAS WALL"),
+        ]));
+        assert_eq!(split("WORM HAS YOU"), Err(GrammarError::UnexpectedWord { position: 2, word: Property(YOU) }));
+    }
+
+    #[test]
+    fn test_as_rule() {
+        assert_eq!(statement("WORM IS YOU").as_rule(), Some((WORM, YOU)));
+        assert_eq!(statement("WORM IS NOT YOU").as_rule(), None);
+        assert_eq!(statement("WORM IS NOT YOU").as_negated_rule(), Some((WORM, YOU)));
+        assert_eq!(statement("WORM IS YOU").as_negated_rule(), None);
+        assert_eq!(statement("WORM IS GOAL").as_rule(), None);
+        assert_eq!(statement("WORM AND WALL IS YOU").as_rule(), None);
+    }
+
+    #[test]
+    fn test_words() {
+        let statement = statement("WORM AND WALL IS YOU");
+        let expected = [Noun(WORM), Operator(AND), Noun(WALL), Operator(IS), Property(YOU)];
+        assert_eq!(statement.words(), expected);
+        assert!((&statement).into_iter().copied().eq(expected));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(statement("WORM IS YOU").to_string(), "WORM IS YOU");
+        assert_eq!(statement("WORM AND WALL IS NOT WIN").to_string(), "WORM AND WALL IS NOT WIN");
+        assert_eq!(Statement::from_iter([]).to_string(), "");
+    }
+
+    #[test]
+    fn test_as_transformation() {
+        assert_eq!(statement("WALL IS WORM").as_transformation(), Some((WALL, WORM)));
+        assert_eq!(statement("WORM IS YOU").as_transformation(), None);
+        assert_eq!(statement("WALL IS NOT WORM").as_transformation(), None);
+    }
+
+    #[test]
+    fn test_error_display() {
+        let error: Box<dyn std::error::Error> = Box::new(split("WORM IS IS YOU").unwrap_err());
+        assert_eq!(error.to_string(), "unexpected IS at word 2");
+        let error: Box<dyn std::error::Error> = Box::new(split("WORM IS").unwrap_err());
+        assert_eq!(error.to_string(), "statement ends before it is complete");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(split("WORM AND WALL"), Err(GrammarError::UnexpectedEnd), "missing verb");
+        assert_eq!(split("WORM IS"), Err(GrammarError::UnexpectedEnd), "missing predicate");
+        assert_eq!(split("WORM IS YOU AND"), Err(GrammarError::UnexpectedEnd), "dangling AND");
+        assert_eq!(split("IS YOU"), Err(GrammarError::UnexpectedWord { position: 0, word: Operator(IS) }), "missing subject");
+        assert_eq!(split("WORM IS IS YOU"), Err(GrammarError::UnexpectedWord { position: 2, word: Operator(IS) }), "IS is not associative");
+        assert_eq!(split("WORM AND IS YOU"), Err(GrammarError::UnexpectedWord { position: 2, word: Operator(IS) }));
+        assert_eq!(split("WORM WALL IS YOU"), Err(GrammarError::UnexpectedWord { position: 1, word: Noun(WALL) }));
+        assert_eq!(split("YOU IS WORM"), Err(GrammarError::UnexpectedWord { position: 0, word: Property(YOU) }));
+        assert_eq!(split("WORM ON GOAL ON WALL IS YOU"), Err(GrammarError::UnexpectedWord { position: 3, word: Operator(ON) }));
+    }
+}
@@ -1,9 +1,99 @@
 //! Physical word blocks.
 
+use std::collections::HashMap;
+use crate::spacial::{vector3i::Vector3i, direction3::Direction3};
 use super::word::Word;
 
+#[cfg(test)]
+mod tests;
+
 /// A physical word block.
 /// Adjacent, grammatically valid Text forms rules.
+///
+/// Text blocks are objects of the noun [`super::word::noun::Noun::TEXT`],
+/// and are [`super::word::property::Property::PUSH`] by default (see [`crate::game_state::GameState::DEFAULT_RULES`]).
+///
+/// A block knows its cell and the direction it's read in, so a loose set of blocks can be read into lines with [`Self::lines()`].
+/// [`crate::level::Level::add_text()`] keeps the position in step with the cell the level holds it in,
+/// and [`crate::level::Level::statements()`] reads every block both across and down, whatever its orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Text {
     word: Word,
+    position: Vector3i,
+    orientation: Direction3,
+}
+
+impl Text {
+    /// Construct a block showing `word` at the origin, read left to right ([`Direction3::East`]).
+    pub fn new(word: Word) -> Self {
+        Self::placed(word, Vector3i::new(0, 0, 0), Direction3::East)
+    }
+
+    /// Construct a block showing `word` in the cell `position`, read towards `orientation`.
+    pub fn placed(word: Word, position: Vector3i, orientation: Direction3) -> Self {
+        Self { word, position, orientation }
+    }
+
+    /// The word on the block.
+    pub fn word(&self) -> Word {
+        self.word
+    }
+
+    /// The cell the block is in.
+    pub fn position(&self) -> Vector3i {
+        self.position
+    }
+
+    /// The direction the block is read in: the next word of its line is one cell that way.
+    pub fn orientation(&self) -> Direction3 {
+        self.orientation
+    }
+
+    /// The same block in the cell `position`.
+    pub fn moved_to(self, position: Vector3i) -> Self {
+        Self { position, ..self }
+    }
+
+    /// The same block, read towards `orientation`.
+    pub fn facing(self, orientation: Direction3) -> Self {
+        Self { orientation, ..self }
+    }
+
+    /// Group `blocks` into lines, each a run of blocks with the same orientation in consecutive cells along it, listed in reading order.
+    /// A block on its own is a line of one.
+    ///
+    /// Lines are ordered by their first cell, top to bottom and then left to right, then by orientation in the order of [`Direction3::ALL`],
+    /// so the result doesn't depend on the order of `blocks`.
+    /// If several blocks have the same cell and orientation, only the last is read.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::{rules::{text::Text, word::Word}, spacial::{vector3i::Vector3i, direction3::Direction3}};
+    /// let words = ["WORM", "IS", "YOU"].map(|word| word.parse::<Word>().unwrap());
+    /// let blocks = (0..).zip(words).map(|(x, word)| Text::placed(word, Vector3i::new(x, 0, 0), Direction3::East));
+    /// let lines = Text::lines(blocks);
+    /// assert_eq!(lines.len(), 1);
+    /// assert_eq!(lines[0].iter().map(Text::word).collect::<Vec<_>>(), words);
+    /// ```
+    pub fn lines(blocks: impl IntoIterator<Item = Text>) -> Vec<Vec<Text>> {
+        let blocks: HashMap<(Vector3i, Direction3), Text> = blocks
+            .into_iter()
+            .map(|text| ((text.position, text.orientation), text))
+            .collect();
+        let mut starts: Vec<Text> = blocks
+            .values()
+            .filter(|text| !blocks.contains_key(&(text.position - text.orientation, text.orientation)))
+            .copied()
+            .collect();
+        starts.sort_by_key(|text| {
+            let Vector3i { x, y, z } = text.position;
+            (z, -y, x, Direction3::ALL.iter().position(|&direction| direction == text.orientation))
+        });
+        starts
+            .into_iter()
+            .map(|start| std::iter::successors(Some(start), |text| blocks.get(&(text.position + text.orientation, text.orientation)).copied())
+                .collect()
+            )
+            .collect()
+    }
 }
@@ -0,0 +1,44 @@
+use super::*;
+
+fn word(word: &str) -> Word {
+    word.parse().unwrap()
+}
+
+mod lines {
+    use super::*;
+
+    #[test]
+    fn test_row_in_order() {
+        let row = [("WORM", 2), ("IS", 3), ("YOU", 4)]
+            .map(|(text, x)| Text::placed(word(text), Vector3i::new(x, 1, 0), Direction3::East));
+        let lines = Text::lines(row.into_iter().rev());
+        assert_eq!(lines, [row.to_vec()], "blocks should be read in order whatever order they're given in");
+        assert_eq!(lines[0].iter().map(Text::word).collect::<Vec<_>>(), [word("WORM"), word("IS"), word("YOU")]);
+    }
+
+    #[test]
+    fn test_orientation() {
+        let across = Text::placed(word("WORM"), Vector3i::new(0, 1, 0), Direction3::East);
+        let down = Text::placed(word("IS"), Vector3i::new(1, 1, 0), Direction3::South);
+        let below = Text::placed(word("YOU"), Vector3i::new(1, 0, 0), Direction3::South);
+        assert_eq!(Text::lines([below, down, across]), [vec![across], vec![down, below]], "only blocks read the same way share a line");
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(Text::lines([]).is_empty());
+    }
+}
+
+mod placed {
+    use super::*;
+
+    #[test]
+    fn test_fields() {
+        let text = Text::placed(word("WIN"), Vector3i::new(1, 2, 3), Direction3::Down);
+        assert_eq!(text.word(), word("WIN"));
+        assert_eq!(text.position(), Vector3i::new(1, 2, 3));
+        assert_eq!(text.orientation(), Direction3::Down);
+        assert_eq!(text.moved_to(Vector3i::new(0, 0, 0)).facing(Direction3::East), Text::new(word("WIN")));
+    }
+}
@@ -4,12 +4,16 @@ pub mod noun;
 pub mod operator;
 pub mod property;
 
+use std::{fmt, str::FromStr};
 use noun::Noun;
 use operator::Operator;
 use property::Property;
 
+#[cfg(test)]
+mod tests;
+
 /// A noun, operator, or property.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Word {
     /// A noun.
     Noun(Noun),
@@ -20,3 +24,52 @@ pub enum Word {
     /// A property.
     Property(Property),
 }
+
+/// The text is not the name of any word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownWordError(pub String);
+
+impl fmt::Display for UnknownWordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown word '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownWordError {}
+
+impl Word {
+    /// The word is a [`Noun`]?
+    pub fn is_noun(self) -> bool {
+        matches!(self, Self::Noun(_))
+    }
+
+    /// The word is an [`Operator`]?
+    pub fn is_operator(self) -> bool {
+        matches!(self, Self::Operator(_))
+    }
+
+    /// The word is a [`Property`]?
+    pub fn is_property(self) -> bool {
+        matches!(self, Self::Property(_))
+    }
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Noun(noun) => noun.fmt(f),
+            Self::Operator(operator) => operator.fmt(f),
+            Self::Property(property) => property.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Word {
+    type Err = UnknownWordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self::Noun)
+            .or_else(|_| s.parse().map(Self::Operator))
+            .or_else(|_| s.parse().map(Self::Property))
+    }
+}
@@ -1,8 +1,54 @@
 //! Categories of objects.
 
+use std::{fmt, str::FromStr};
+use super::UnknownWordError;
+
 /// The category of object the rule is applied to.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Noun {
     /// All worms.
     WORM,
+    /// All goal cells.
+    GOAL,
+    /// All wall cells.
+    WALL,
+    /// All text blocks.
+    TEXT,
+}
+
+impl Noun {
+    /// Every noun, in declaration order.
+    pub const ALL: [Noun; 4] = [
+        Self::WORM,
+        Self::GOAL,
+        Self::WALL,
+        Self::TEXT,
+    ];
+
+    /// How the noun is written. Parsing and printing both use this.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::WORM => "WORM",
+            Self::GOAL => "GOAL",
+            Self::WALL => "WALL",
+            Self::TEXT => "TEXT",
+        }
+    }
+}
+
+impl fmt::Display for Noun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Noun {
+    type Err = UnknownWordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|noun| noun.name() == s)
+            .ok_or_else(|| UnknownWordError(s.to_string()))
+    }
 }
@@ -1,7 +1,10 @@
 //! Applies properties to nouns.
 
+use std::{fmt, str::FromStr};
+use super::UnknownWordError;
+
 /// A helper/modifier for describing how two elements affect each other.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Operator {
     /// `[Noun(a), IS, Noun(b)]` => Each instance of `a` is immediately replaced with `b`.<br/>
     /// `[Noun(a), IS, Form(b)]` => Each instance of `a` participates in `b`.
@@ -16,3 +19,47 @@ pub enum Operator {
     /// `[a, ON, b, Operator(x), Grammar(d)]`
     ON,
 }
+
+impl Operator {
+    /// Every operator, in declaration order.
+    pub const ALL: [Operator; 5] = [
+        Self::IS,
+        Self::HAS,
+        Self::AND,
+        Self::NOT,
+        Self::ON,
+    ];
+
+    /// Joins or modifies terms (`AND`, `NOT`) rather than relating the two sides of a statement?
+    pub fn is_connective(self) -> bool {
+        matches!(self, Self::AND | Self::NOT)
+    }
+
+    /// How the operator is written. Parsing and printing both use this.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::IS  => "IS",
+            Self::HAS => "HAS",
+            Self::AND => "AND",
+            Self::NOT => "NOT",
+            Self::ON  => "ON",
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Operator {
+    type Err = UnknownWordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|operator| operator.name() == s)
+            .ok_or_else(|| UnknownWordError(s.to_string()))
+    }
+}
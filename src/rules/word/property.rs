@@ -1,8 +1,62 @@
 //! Properties that nouns can participate in.
 
+use std::{fmt, str::FromStr};
+use super::UnknownWordError;
+
 /// A trait or property that can be conditionally participated in.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Property {
     /// Controlled by player input.
     YOU,
+    /// Touching it with something that is [`Property::YOU`] wins the level.
+    WIN,
+    /// Moves away when something crawls into it, if it has room.
+    PUSH,
+    /// Destroys anything that is [`Property::YOU`] touching it.
+    DEFEAT,
+    /// Only interacts with other [`Property::FLOAT`] things, passing over everything else (and vice versa).
+    FLOAT,
+    /// Moves on its own every step, turning around when blocked.
+    MOVE,
+}
+
+impl Property {
+    /// Every property, in declaration order.
+    pub const ALL: [Property; 6] = [
+        Self::YOU,
+        Self::WIN,
+        Self::PUSH,
+        Self::DEFEAT,
+        Self::FLOAT,
+        Self::MOVE,
+    ];
+
+    /// How the property is written. Parsing and printing both use this.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::YOU    => "YOU",
+            Self::WIN    => "WIN",
+            Self::PUSH   => "PUSH",
+            Self::DEFEAT => "DEFEAT",
+            Self::FLOAT  => "FLOAT",
+            Self::MOVE   => "MOVE",
+        }
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Property {
+    type Err = UnknownWordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|property| property.name() == s)
+            .ok_or_else(|| UnknownWordError(s.to_string()))
+    }
 }
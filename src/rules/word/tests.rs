@@ -0,0 +1,75 @@
+use super::*;
+use super::operator::Operator;
+
+mod round_trip {
+    use super::*;
+
+    #[test]
+    fn test_noun() {
+        for noun in Noun::ALL {
+            assert_eq!(noun.to_string().parse::<Noun>(), Ok(noun));
+        }
+    }
+
+    #[test]
+    fn test_operator() {
+        for operator in Operator::ALL {
+            assert_eq!(operator.to_string().parse::<Operator>(), Ok(operator));
+        }
+    }
+
+    #[test]
+    fn test_property() {
+        for property in Property::ALL {
+            assert_eq!(property.to_string().parse::<Property>(), Ok(property));
+        }
+    }
+
+    #[test]
+    fn test_word() {
+        let words = Noun::ALL.map(Word::Noun).into_iter()
+            .chain(Operator::ALL.map(Word::Operator))
+            .chain(Property::ALL.map(Word::Property));
+        for word in words {
+            assert_eq!(word.to_string().parse::<Word>(), Ok(word));
+        }
+    }
+}
+
+mod parse {
+    use super::*;
+
+    #[test]
+    fn test_spelling() {
+        assert_eq!("WORM".parse::<Word>(), Ok(Word::Noun(Noun::WORM)));
+        assert_eq!("IS".parse::<Word>(), Ok(Word::Operator(Operator::IS)));
+        assert_eq!("YOU".parse::<Word>(), Ok(Word::Property(Property::YOU)));
+    }
+
+    #[test]
+    fn test_unknown() {
+        assert_eq!("BABA".parse::<Word>(), Err(UnknownWordError("BABA".to_string())));
+        assert!("worm".parse::<Noun>().is_err(), "words are uppercase");
+        assert!("YOU".parse::<Noun>().is_err(), "a property is not a noun");
+    }
+}
+
+mod classify {
+    use super::*;
+
+    #[test]
+    fn test_word() {
+        let noun = Word::Noun(Noun::WORM);
+        let operator = Word::Operator(Operator::IS);
+        let property = Word::Property(Property::YOU);
+        assert!(noun.is_noun() && !noun.is_operator() && !noun.is_property());
+        assert!(!operator.is_noun() && operator.is_operator() && !operator.is_property());
+        assert!(!property.is_noun() && !property.is_operator() && property.is_property());
+    }
+
+    #[test]
+    fn test_connective() {
+        let connectives: Vec<_> = Operator::ALL.into_iter().filter(|operator| operator.is_connective()).collect();
+        assert_eq!(connectives, [Operator::AND, Operator::NOT]);
+    }
+}
@@ -0,0 +1,97 @@
+//! Versioned envelope for saved levels.
+//!
+//! A save is the line `version <n>` followed by a level in the [`crate::level::parse`] format.
+//! The version is checked before anything else is read, so a save from a format this build doesn't know
+//! is rejected outright instead of being misread as the current one.
+//!
+//! Example:
+//! ```text
+//! version 1
+//! bounds 6 1 1
+//! worm 1 0 0 <
+//! ```
+
+use std::{fmt, str::FromStr};
+use crate::level::{Level, parse::LevelParseError};
+
+#[cfg(test)]
+mod tests;
+
+/// The version written by [`Save::new()`].
+/// Bump this whenever the save format changes, and teach [`Save::migrate()`] to read the old one.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// A level tagged with the version of the format it was saved in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Save {
+    /// The format version the save was written in.
+    pub version: u32,
+    /// The saved level.
+    pub level: Level,
+}
+
+/// The reason a [`Save`] could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveError {
+    /// The save doesn't start with a `version` line.
+    MissingVersion,
+    /// The save is from a version this build can't read.
+    UnsupportedVersion(u32),
+    /// The version is supported, but the level in it is not.
+    Level(LevelParseError),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingVersion => write!(f, "save is missing its version line"),
+            Self::UnsupportedVersion(version) => write!(f, "save version {version} is not supported"),
+            Self::Level(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl Save {
+    /// Wrap a level in the current version.
+    pub fn new(level: Level) -> Self {
+        Self { version: CURRENT_VERSION, level }
+    }
+
+    /// Read the body of a save written in `version`, upgrading it to [`CURRENT_VERSION`].
+    pub fn migrate(version: u32, body: &str) -> Result<Self, SaveError> {
+        match version {
+            1 => body
+                .parse()
+                .map(Self::new)
+                .map_err(|e| SaveError::Level(match e {
+                    // Account for the version line
+                    LevelParseError::Syntax { line, message } if line != 0 => LevelParseError::Syntax { line: line + 1, message },
+                    e => e,
+                })),
+            _ => Err(SaveError::UnsupportedVersion(version)),
+        }
+    }
+}
+
+impl fmt::Display for Save {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "version {}", self.version)?;
+        write!(f, "{}", self.level)
+    }
+}
+
+impl FromStr for Save {
+    type Err = SaveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first, body) = s.split_once('\n').unwrap_or((s, ""));
+        let version = first
+            .trim()
+            .strip_prefix("version ")
+            .and_then(|version| version.trim().parse().ok())
+            .ok_or(SaveError::MissingVersion)?;
+        Self::migrate(version, body)
+    }
+}
@@ -0,0 +1,43 @@
+use super::*;
+use crate::{spacial::{vector3i::Vector3i, direction3::Direction3}, worm::Worm};
+
+fn corridor() -> Level {
+    let mut level = Level::new(Vector3i::new(6, 1, 1));
+    level.add_wall(Vector3i::new(5, 0, 0));
+    level.add_goal(Vector3i::new(4, 0, 0));
+    level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+    level
+}
+
+mod load {
+    use super::*;
+
+    #[test]
+    fn test_v1() {
+        let save: Save = "version 1\nbounds 6 1 1\nwall 5 0 0\ngoal 4 0 0\nworm 1 0 0 <\n".parse().unwrap();
+        assert_eq!(save, Save { version: 1, level: corridor() });
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let save = Save::new(corridor());
+        assert_eq!(save.to_string().parse::<Save>(), Ok(save));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let text = Save::new(corridor()).to_string().replacen("version 1", "version 2", 1);
+        assert_eq!(text.parse::<Save>(), Err(SaveError::UnsupportedVersion(2)));
+    }
+
+    #[test]
+    fn test_missing_version() {
+        assert_eq!("bounds 6 1 1\n".parse::<Save>(), Err(SaveError::MissingVersion));
+    }
+
+    #[test]
+    fn test_level_error_line() {
+        let result = "version 1\nbounds 6 1 1\nwal 5 0 0\n".parse::<Save>();
+        assert!(matches!(result, Err(SaveError::Level(LevelParseError::Syntax { line: 3, .. }))), "line numbers should count the version line");
+    }
+}
@@ -0,0 +1,49 @@
+//! Searching for solutions to a level.
+
+use std::collections::{HashSet, VecDeque};
+use crate::{
+    spacial::direction3::Direction3,
+    game_state::GameState,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The shortest sequence of inputs that wins the game from `start`, or [`None`] if it can't be won within `max_depth` steps.
+/// An already-won game is solved by no inputs.
+///
+/// Searches breadth-first over [`GameState::legal_moves()`], visiting each distinct state at most once.
+pub fn solve(start: &GameState, max_depth: usize) -> Option<Vec<Direction3>> {
+    if start.is_won() {
+        return Some(Vec::new());
+    }
+    // Worms cache their cells inside a cell, but the cache is left out of hashing and equality
+    #[allow(clippy::mutable_key_type)]
+    let mut visited = HashSet::from([start.clone()]);
+    // Fresh states don't drag the undo history of every move before them along
+    let fresh = |state: &GameState| {
+        let mut fresh = state.clone();
+        fresh.clear_history();
+        fresh
+    };
+    let mut queue = VecDeque::from([(fresh(start), Vec::new())]);
+    while let Some((state, path)) = queue.pop_front() {
+        if path.len() >= max_depth {
+            continue;
+        }
+        for direction in state.legal_moves() {
+            let mut next = fresh(&state);
+            let report = next.step(direction);
+            if !visited.insert(next.clone()) {
+                continue;
+            }
+            let mut path = path.clone();
+            path.push(direction);
+            if report.won {
+                return Some(path);
+            }
+            queue.push_back((next, path));
+        }
+    }
+    None
+}
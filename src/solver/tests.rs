@@ -0,0 +1,71 @@
+use super::*;
+use crate::{
+    spacial::vector3i::Vector3i,
+    level::Level,
+    worm::Worm,
+    rules::{ruleset::Ruleset, text::Text, word::{Word, noun::Noun, property::Property}},
+};
+
+fn rules() -> Ruleset {
+    Ruleset::from_iter([(Noun::WORM, Property::YOU), (Noun::GOAL, Property::WIN)])
+}
+
+mod solve {
+    use super::*;
+
+    #[test]
+    fn test_two_moves() {
+        let mut level = Level::new(Vector3i::new(6, 1, 1));
+        level.add_goal(Vector3i::new(3, 0, 0));
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let game = GameState::new(level, rules());
+        assert_eq!(solve(&game, 8), Some(vec![Direction3::East, Direction3::East]));
+        assert_eq!(solve(&game, 1), None, "the solution is deeper than allowed");
+    }
+
+    #[test]
+    fn test_already_won() {
+        let mut level = Level::new(Vector3i::new(6, 1, 1));
+        level.add_goal(Vector3i::new(1, 0, 0));
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        assert_eq!(solve(&GameState::new(level, rules()), 0), Some(vec![]));
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let mut level = Level::new(Vector3i::new(6, 1, 1));
+        level.add_wall(Vector3i::new(3, 0, 0));
+        level.add_goal(Vector3i::new(5, 0, 0));
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        assert_eq!(solve(&GameState::new(level, rules()), 10), None);
+    }
+
+    #[test]
+    fn test_replays_to_win() {
+        // Going straight means pushing the text in the way, so without TEXT IS PUSH the worm has to go around it
+        let mut level = Level::new(Vector3i::new(5, 3, 1));
+        level.add_text(Vector3i::new(2, 1, 0), Text::new(Word::Noun(Noun::WORM)));
+        level.add_goal(Vector3i::new(3, 1, 0));
+        level.add_worm(Worm::new(Vector3i::new(1, 1, 0), [Direction3::West]));
+        let mut game = GameState::new(level, rules());
+        game.set_base_rules(rules());
+
+        let moves = solve(&game, 8).expect("the worm can go around the text");
+        for direction in moves {
+            game.step(direction);
+        }
+        assert!(game.is_won(), "the moves should win the game they were found for");
+    }
+
+    #[test]
+    fn test_without_default_push() {
+        let mut level = Level::new(Vector3i::new(6, 1, 1));
+        level.add_text(Vector3i::new(2, 0, 0), Text::new(Word::Noun(Noun::WORM)));
+        level.add_goal(Vector3i::new(2, 0, 0));
+        level.add_worm(Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+        let mut game = GameState::new(level, rules());
+        assert_eq!(solve(&game, 4), Some(vec![Direction3::East]), "pushing the text off the goal should win");
+        game.set_base_rules(rules());
+        assert_eq!(solve(&game, 4), None, "without TEXT IS PUSH, the text should block the only way to the goal");
+    }
+}
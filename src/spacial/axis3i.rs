@@ -0,0 +1,60 @@
+//! A 3D grid axis.
+
+use std::{fmt, str::FromStr};
+
+#[cfg(test)]
+mod tests;
+
+/// One of the three axes of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis3i {
+    /// The [`super::vector3i::Vector3i::x`] axis. Rotating about it leaves x fixed.
+    X,
+    /// The [`super::vector3i::Vector3i::y`] axis. Rotating about it leaves y fixed.
+    Y,
+    /// The [`super::vector3i::Vector3i::z`] axis. Rotating about it leaves z fixed.
+    Z,
+}
+
+impl Axis3i {
+    /// Every axis, in declaration order.
+    pub const ALL: [Axis3i; 3] = [
+        Self::X,
+        Self::Y,
+        Self::Z,
+    ];
+}
+
+/// The text is not the name of an axis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownAxisError(pub String);
+
+impl fmt::Display for UnknownAxisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown axis '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownAxisError {}
+
+/// The lowercase name of the coordinate, e.g. `z`, as in `z=0` for the plane where z is fixed at 0.
+impl fmt::Display for Axis3i {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::X => "x",
+            Self::Y => "y",
+            Self::Z => "z",
+        })
+    }
+}
+
+impl FromStr for Axis3i {
+    type Err = UnknownAxisError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|axis| axis.to_string() == s)
+            .ok_or_else(|| UnknownAxisError(s.to_string()))
+    }
+}
@@ -0,0 +1,21 @@
+use super::*;
+
+mod parse {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for axis in Axis3i::ALL {
+            assert_eq!(axis.to_string().parse::<Axis3i>(), Ok(axis));
+        }
+        assert_eq!("x".parse::<Axis3i>(), Ok(Axis3i::X));
+        assert_eq!("y".parse::<Axis3i>(), Ok(Axis3i::Y));
+        assert_eq!("z".parse::<Axis3i>(), Ok(Axis3i::Z));
+    }
+
+    #[test]
+    fn test_unknown() {
+        assert_eq!("w".parse::<Axis3i>(), Err(UnknownAxisError("w".to_string())));
+        assert!("Z".parse::<Axis3i>().is_err(), "axes are lowercase");
+    }
+}
@@ -1,10 +1,13 @@
 //! A 3D cardinal direction.
 
-use std::{ops::*, cmp::*};
-use super::vector3i::Vector3i;
+use std::{ops::*, cmp::*, fmt};
+use super::{vector3i::Vector3i, axis3i::Axis3i};
+
+#[cfg(test)]
+mod tests;
 
 /// A 3D cardinal direction.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Direction3 {
     /// Positive [`Vector3i::x`].
     East =  0b100,
@@ -20,6 +23,181 @@ pub enum Direction3 {
     Down = -0b001,
 }
 
+/// The vector is not one step along a single axis, so it has no [`Direction3`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotCardinalError(pub Vector3i);
+
+impl fmt::Display for NotCardinalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a single step along one axis", self.0)
+    }
+}
+
+impl std::error::Error for NotCardinalError {}
+
+impl Direction3 {
+    /// Every direction, in declaration order.
+    pub const ALL: [Direction3; 6] = [
+        Self::East,
+        Self::West,
+        Self::North,
+        Self::South,
+        Self::Up,
+        Self::Down,
+    ];
+
+    /// The direction of the single step from `from` to `to`,
+    /// or [`None`] if `to` is not orthogonally adjacent to `from`.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::{direction3::Direction3, vector3i::Vector3i};
+    /// assert_eq!(Direction3::between(Vector3i::new(1, 1, 1), Vector3i::new(1, 0, 1)), Some(Direction3::South));
+    /// assert_eq!(Direction3::between(Vector3i::new(1, 1, 1), Vector3i::new(2, 2, 1)), None);
+    /// ```
+    pub fn between(from: Vector3i, to: Vector3i) -> Option<Direction3> {
+        Direction3::try_from(to - from).ok()
+    }
+
+    /// Resolve raw input along each axis (negative, zero, or positive, e.g. from keys or a gamepad) into a single direction.
+    /// Horizontal input takes priority over vertical, which takes priority over depth.
+    /// [`None`] if there is no input at all.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::direction3::Direction3;
+    /// assert_eq!(Direction3::from_input(-1, 1, 0), Some(Direction3::West));
+    /// assert_eq!(Direction3::from_input(0, 0, 1), Some(Direction3::Up));
+    /// assert_eq!(Direction3::from_input(0, 0, 0), None);
+    /// ```
+    pub fn from_input(horizontal: i32, vertical: i32, depth: i32) -> Option<Direction3> {
+        use Direction3::*;
+        [(horizontal, East, West), (vertical, North, South), (depth, Up, Down)]
+            .into_iter()
+            .find_map(|(input, positive, negative)| match input.signum() {
+                1 => Some(positive),
+                -1 => Some(negative),
+                _ => None,
+            })
+    }
+
+    /// The two directions point exactly away from each other?
+    pub fn is_opposite(self, other: Direction3) -> bool {
+        self == -other
+    }
+
+    /// A compact single-byte encoding: the enum's bit layout as a signed byte.
+    pub fn to_byte(self) -> u8 {
+        self as i8 as u8
+    }
+
+    /// Decode a byte made by [`Self::to_byte()`], or [`None`] if it isn't one.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::direction3::Direction3;
+    /// assert_eq!(Direction3::from_byte(Direction3::Down.to_byte()), Some(Direction3::Down));
+    /// assert_eq!(Direction3::from_byte(0), None);
+    /// ```
+    pub fn from_byte(byte: u8) -> Option<Direction3> {
+        Direction3::ALL
+            .into_iter()
+            .find(|direction| direction.to_byte() == byte)
+    }
+
+    /// The axis the direction points along, and `1` or `-1` for which way along it.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::{direction3::Direction3, axis3i::Axis3i};
+    /// assert_eq!(Direction3::West.axis_and_sign(), (Axis3i::X, -1));
+    /// assert_eq!(Direction3::Up.axis_and_sign(), (Axis3i::Z, 1));
+    /// ```
+    pub fn axis_and_sign(self) -> (Axis3i, i32) {
+        use Direction3::*;
+        match self {
+            East  => (Axis3i::X,  1),
+            West  => (Axis3i::X, -1),
+            North => (Axis3i::Y,  1),
+            South => (Axis3i::Y, -1),
+            Up    => (Axis3i::Z,  1),
+            Down  => (Axis3i::Z, -1),
+        }
+    }
+
+    /// The direction along `axis` that way `sign` points; the inverse of [`Self::axis_and_sign()`].
+    /// [`None`] if `sign` is zero.
+    pub fn from_axis_and_sign(axis: Axis3i, sign: i32) -> Option<Direction3> {
+        Direction3::ALL
+            .into_iter()
+            .find(|direction| direction.axis_and_sign() == (axis, sign.signum()))
+    }
+
+    /// The signed length of the direction along `axis`: `1` or `-1` if it points along the axis, otherwise `0`.
+    pub fn component(self, axis: Axis3i) -> i32 {
+        match self.axis_and_sign() {
+            (along, sign) if along == axis => sign,
+            _ => 0,
+        }
+    }
+
+    /// Reflect across the plane perpendicular to `axis`: directions along `axis` flip, and the rest are unchanged.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::{direction3::Direction3, axis3i::Axis3i};
+    /// assert_eq!(Direction3::East.mirror(Axis3i::X), Direction3::West);
+    /// assert_eq!(Direction3::North.mirror(Axis3i::X), Direction3::North);
+    /// ```
+    pub fn mirror(self, axis: Axis3i) -> Direction3 {
+        if self.axis_and_sign().0 == axis { -self } else { self }
+    }
+
+    /// A clockwise quarter turn about `axis`, as seen looking from the positive end of the axis towards the origin.
+    /// Directions along `axis` are unchanged.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::{direction3::Direction3, axis3i::Axis3i};
+    /// assert_eq!(Direction3::North.turn_right(Axis3i::Z), Direction3::East);
+    /// ```
+    pub fn turn_right(self, axis: Axis3i) -> Direction3 {
+        use Direction3::*;
+        match (axis, self) {
+            (Axis3i::X, North) => Down,
+            (Axis3i::X, Down ) => South,
+            (Axis3i::X, South) => Up,
+            (Axis3i::X, Up   ) => North,
+            (Axis3i::Y, East ) => Up,
+            (Axis3i::Y, Up   ) => West,
+            (Axis3i::Y, West ) => Down,
+            (Axis3i::Y, Down ) => East,
+            (Axis3i::Z, North) => East,
+            (Axis3i::Z, East ) => South,
+            (Axis3i::Z, South) => West,
+            (Axis3i::Z, West ) => North,
+            (_, direction) => direction,
+        }
+    }
+
+    /// A counterclockwise quarter turn about `axis`; the inverse of [`Self::turn_right()`].
+    pub fn turn_left(self, axis: Axis3i) -> Direction3 {
+        self.turn_right(axis)
+            .turn_right(axis)
+            .turn_right(axis)
+    }
+
+    /// Any number of quarter turns about `axis`.
+    /// Positive turns are clockwise like [`Self::turn_right()`] and negative turns are counterclockwise like [`Self::turn_left()`].
+    pub fn rotate(self, axis: Axis3i, quarter_turns: i32) -> Direction3 {
+        if self.axis_and_sign().0 == axis {
+            return self;
+        }
+        (0..quarter_turns.rem_euclid(4))
+            .fold(self, |direction, _| direction.turn_right(axis))
+    }
+}
+
 // All Vector3i methods can be converted to Vector3 methods with minimal change
 // But I haven't found a need to do that yet, so eh.
 
@@ -47,6 +225,17 @@ impl From<Direction3> for Vector3i {
     }
 }
 
+impl TryFrom<Vector3i> for Direction3 {
+    type Error = NotCardinalError;
+
+    fn try_from(value: Vector3i) -> Result<Self, Self::Error> {
+        Direction3::ALL
+            .into_iter()
+            .find(|&direction| Vector3i::from(direction) == value)
+            .ok_or(NotCardinalError(value))
+    }
+}
+
 impl Neg for Direction3 {
     type Output = Self;
 
@@ -0,0 +1,188 @@
+use super::*;
+
+mod rotate {
+    use super::*;
+
+    #[test]
+    fn test_full_turn() {
+        for axis in Axis3i::ALL {
+            for direction in Direction3::ALL {
+                assert_eq!(direction.rotate(axis, 4), direction, "4 turns about {axis:?} should be identity");
+                assert_eq!(direction.rotate(axis, -8), direction, "-8 turns about {axis:?} should be identity");
+            }
+        }
+    }
+
+    #[test]
+    fn test_negative() {
+        for axis in Axis3i::ALL {
+            for direction in Direction3::ALL {
+                assert_eq!(direction.rotate(axis, -1), direction.turn_left(axis));
+                assert_eq!(direction.rotate(axis, 5), direction.turn_right(axis));
+            }
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        for axis in Axis3i::ALL {
+            for direction in Direction3::ALL {
+                assert_eq!(direction.turn_right(axis).turn_left(axis), direction);
+            }
+        }
+    }
+
+    #[test]
+    fn test_about_z() {
+        use Direction3::*;
+        assert_eq!(North.turn_right(Axis3i::Z), East);
+        assert_eq!(North.turn_left(Axis3i::Z), West);
+        assert_eq!(North.rotate(Axis3i::Z, 2), South);
+        assert_eq!(Up.rotate(Axis3i::Z, 1), Up, "direction along the axis should be unchanged");
+    }
+}
+
+mod component {
+    use super::*;
+
+    #[test]
+    fn test_all() {
+        for direction in Direction3::ALL {
+            let vector = Vector3i::from(direction);
+            assert_eq!(direction.component(Axis3i::X), vector.x, "{direction:?} along X");
+            assert_eq!(direction.component(Axis3i::Y), vector.y, "{direction:?} along Y");
+            assert_eq!(direction.component(Axis3i::Z), vector.z, "{direction:?} along Z");
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(Direction3::East.component(Axis3i::X), 1);
+        assert_eq!(Direction3::West.component(Axis3i::X), -1);
+        assert_eq!(Direction3::North.component(Axis3i::X), 0);
+    }
+}
+
+mod between {
+    use super::*;
+
+    #[test]
+    fn test_neighbors() {
+        let from = Vector3i::new(3, -2, 5);
+        for direction in Direction3::ALL {
+            assert_eq!(Direction3::between(from, from + direction), Some(direction));
+        }
+    }
+
+    #[test]
+    fn test_not_neighbors() {
+        let from = Vector3i::new(3, -2, 5);
+        assert_eq!(Direction3::between(from, from), None, "a cell is not its own neighbor");
+        assert_eq!(Direction3::between(from, from + Vector3i::new(2, 0, 0)), None, "too far");
+        assert_eq!(Direction3::between(from, from + Vector3i::new(1, 1, 0)), None, "diagonal");
+    }
+
+    #[test]
+    fn test_try_from() {
+        assert_eq!(Direction3::try_from(Vector3i::new(0, 0, -1)), Ok(Direction3::Down));
+        assert_eq!(Direction3::try_from(Vector3i::new(0, 3, 0)), Err(NotCardinalError(Vector3i::new(0, 3, 0))));
+    }
+}
+
+mod to_byte {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for direction in Direction3::ALL {
+            assert_eq!(Direction3::from_byte(direction.to_byte()), Some(direction));
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        let valid: Vec<u8> = Direction3::ALL.map(Direction3::to_byte).into();
+        for byte in (0..=u8::MAX).filter(|byte| !valid.contains(byte)) {
+            assert_eq!(Direction3::from_byte(byte), None, "byte {byte}");
+        }
+    }
+}
+
+mod is_opposite {
+    use super::*;
+
+    #[test]
+    fn test_all_pairs() {
+        assert!(Direction3::East.is_opposite(Direction3::West));
+        assert!(!Direction3::East.is_opposite(Direction3::North));
+        for a in Direction3::ALL {
+            for b in Direction3::ALL {
+                assert_eq!(a.is_opposite(b), Vector3i::from(a) + Vector3i::from(b) == Vector3i::new(0, 0, 0), "{a:?} and {b:?}");
+            }
+        }
+    }
+}
+
+mod mirror {
+    use super::*;
+
+    #[test]
+    fn test_twice_is_identity() {
+        for axis in Axis3i::ALL {
+            for direction in Direction3::ALL {
+                assert_eq!(direction.mirror(axis).mirror(axis), direction);
+                assert_eq!(direction.mirror(axis) == direction, direction.component(axis) == 0);
+            }
+        }
+    }
+}
+
+mod from_input {
+    use super::*;
+
+    #[test]
+    fn test_priority() {
+        use Direction3::*;
+        for vertical in -1..=1 {
+            for depth in -1..=1 {
+                assert_eq!(Direction3::from_input(1, vertical, depth), Some(East), "horizontal should win");
+                assert_eq!(Direction3::from_input(-1, vertical, depth), Some(West), "horizontal should win");
+            }
+        }
+        for depth in -1..=1 {
+            assert_eq!(Direction3::from_input(0, 1, depth), Some(North), "vertical should beat depth");
+            assert_eq!(Direction3::from_input(0, -1, depth), Some(South), "vertical should beat depth");
+        }
+        assert_eq!(Direction3::from_input(0, 0, 1), Some(Up));
+        assert_eq!(Direction3::from_input(0, 0, -1), Some(Down));
+        assert_eq!(Direction3::from_input(0, 0, 0), None);
+    }
+
+    #[test]
+    fn test_magnitude() {
+        assert_eq!(Direction3::from_input(-5, 0, 0), Some(Direction3::West), "only the sign should matter");
+    }
+}
+
+mod axis_and_sign {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for direction in Direction3::ALL {
+            let (axis, sign) = direction.axis_and_sign();
+            assert!(sign == 1 || sign == -1, "{direction:?}");
+            let [x, y, z] = Axis3i::ALL.map(|other| if other == axis { sign } else { 0 });
+            assert_eq!(Vector3i::from(direction), Vector3i::new(x, y, z), "{direction:?}");
+            assert_eq!(Direction3::from_axis_and_sign(axis, sign), Some(direction));
+            assert_eq!(Direction3::from_axis_and_sign(axis, sign * 5), Some(direction), "only the sign matters");
+        }
+    }
+
+    #[test]
+    fn test_zero_sign() {
+        for axis in Axis3i::ALL {
+            assert_eq!(Direction3::from_axis_and_sign(axis, 0), None);
+        }
+    }
+}
@@ -2,3 +2,4 @@
 
 pub mod vector3i;
 pub mod direction3;
+pub mod axis3i;
@@ -1,9 +1,15 @@
 //! Integer [`raylib::prelude::Vector3`].
 
-use std::{ops::*, cmp::*};
+use std::{ops::*, cmp::*, fmt};
+use super::{direction3::Direction3, axis3i::Axis3i};
+#[cfg(feature = "raylib")]
+use raylib::prelude::Vector3;
+
+#[cfg(test)]
+mod tests;
 
 /// 3D grid position.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Vector3i {
     /// x-coordinate.
     pub x: i32,
@@ -14,10 +20,206 @@ pub struct Vector3i {
 }
 
 impl Vector3i {
+    /// Bits per component in [`Self::to_packed()`].
+    pub const PACKED_BITS: u32 = 21;
+
     /// Construct a [`Vector3i`]
     pub fn new(x: i32, y: i32, z: i32) -> Self {
         Self { x, y, z }
     }
+
+    /// Euclidean remainder of each component.
+    /// Every component of the result is in `0..rhs` (for positive `rhs`), wrapping negatives around.
+    ///
+    /// Panics if any component of `rhs` is zero.
+    pub fn rem_euclid(self, rhs: Vector3i) -> Self {
+        Self {
+            x: self.x.rem_euclid(rhs.x),
+            y: self.y.rem_euclid(rhs.y),
+            z: self.z.rem_euclid(rhs.z),
+        }
+    }
+
+    /// The number of orthogonal steps from the origin; the sum of the absolute components.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::vector3i::Vector3i;
+    /// assert_eq!(Vector3i::new(1, -2, 3).manhattan_length(), 6);
+    /// ```
+    pub fn manhattan_length(self) -> u32 {
+        self.x.unsigned_abs() + self.y.unsigned_abs() + self.z.unsigned_abs()
+    }
+
+    /// The number of orthogonal steps between two cells.
+    pub fn manhattan_distance(self, other: Vector3i) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// The direction of a single step from `self` towards `target`, along whichever axis `target` is furthest on.
+    /// Ties go to the earlier axis, X before Y before Z.
+    /// Returns [`None`] if `self` is already at `target`.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::{vector3i::Vector3i, direction3::Direction3};
+    /// let origin = Vector3i::new(0, 0, 0);
+    /// assert_eq!(origin.step_toward(Vector3i::new(1, -3, 0)), Some(Direction3::South));
+    /// assert_eq!(origin.step_toward(Vector3i::new(2, 2, 2)), Some(Direction3::East));
+    /// assert_eq!(origin.step_toward(origin), None);
+    /// ```
+    pub fn step_toward(self, target: Vector3i) -> Option<Direction3> {
+        let Vector3i { x, y, z } = target - self;
+        let (positive, negative, component) = [
+            (Direction3::East,  Direction3::West,  x),
+            (Direction3::North, Direction3::South, y),
+            (Direction3::Up,    Direction3::Down,  z),
+        ]
+            .into_iter()
+            .rev() // max_by_key keeps the last maximum
+            .max_by_key(|(_, _, component)| component.unsigned_abs())?;
+        match component.cmp(&0) {
+            Ordering::Greater => Some(positive),
+            Ordering::Less => Some(negative),
+            Ordering::Equal => None,
+        }
+    }
+
+    /// The coordinate along `axis`, e.g. `z` for [`Axis3i::Z`].
+    pub fn component(self, axis: Axis3i) -> i32 {
+        match axis {
+            Axis3i::X => self.x,
+            Axis3i::Y => self.y,
+            Axis3i::Z => self.z,
+        }
+    }
+
+    /// The smaller of each pair of components.
+    pub fn component_min(self, other: Vector3i) -> Self {
+        Self::new(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+    }
+
+    /// The larger of each pair of components.
+    pub fn component_max(self, other: Vector3i) -> Self {
+        Self::new(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+    }
+
+    /// The number of cells in a box with `self` as its size, i.e. `|x| * |y| * |z|`.
+    /// Widened to [`i64`] so large boxes don't overflow.
+    pub fn volume(self) -> i64 {
+        [self.x, self.y, self.z]
+            .into_iter()
+            .map(|component| i64::from(component.unsigned_abs()))
+            .product()
+    }
+
+    /// Pack the three components into a single integer, for compact keys in large maps.
+    /// Returns [`None`] unless every component fits in [`Self::PACKED_BITS`] bits as a signed integer.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::vector3i::Vector3i;
+    /// let cell = Vector3i::new(-3, 0, 70000);
+    /// assert_eq!(Vector3i::from_packed(cell.to_packed().unwrap()), cell);
+    /// assert_eq!(Vector3i::new(1 << 20, 0, 0).to_packed(), None);
+    /// ```
+    pub fn to_packed(self) -> Option<u64> {
+        const MIN: i32 = -(1 << (Vector3i::PACKED_BITS - 1));
+        const MAX: i32 = (1 << (Vector3i::PACKED_BITS - 1)) - 1;
+        const MASK: u64 = (1 << Vector3i::PACKED_BITS) - 1;
+        let pack = |component: i32| (MIN..=MAX)
+            .contains(&component)
+            .then_some(component as u64 & MASK);
+        Some(pack(self.x)? << (2 * Self::PACKED_BITS) | pack(self.y)? << Self::PACKED_BITS | pack(self.z)?)
+    }
+
+    /// Unpack a value made by [`Self::to_packed()`].
+    /// Bits above the lowest `3 * PACKED_BITS` are ignored.
+    pub fn from_packed(packed: u64) -> Self {
+        // Shift each component to the top, then back down, to sign-extend it
+        let unpack = |offset: u32| ((packed << (64 - Self::PACKED_BITS - offset)) as i64 >> (64 - Self::PACKED_BITS)) as i32;
+        Self::new(unpack(2 * Self::PACKED_BITS), unpack(Self::PACKED_BITS), unpack(0))
+    }
+
+    /// Every cell in the inclusive box from `lo` to `hi`, varying x fastest, then y, then z, each increasing.
+    /// Empty if any component of `lo` is greater than the same component of `hi`.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::vector3i::Vector3i;
+    /// let cells: Vec<_> = Vector3i::iter_box(Vector3i::new(0, 0, 0), Vector3i::new(1, 1, 0)).collect();
+    /// assert_eq!(cells, [Vector3i::new(0, 0, 0), Vector3i::new(1, 0, 0), Vector3i::new(0, 1, 0), Vector3i::new(1, 1, 0)]);
+    /// ```
+    pub fn iter_box(lo: Vector3i, hi: Vector3i) -> impl Iterator<Item = Vector3i> {
+        (lo.z..=hi.z).flat_map(move |z| (lo.y..=hi.y)
+            .flat_map(move |y| (lo.x..=hi.x)
+                .map(move |x| Vector3i::new(x, y, z))
+            )
+        )
+    }
+
+    /// The six orthogonally adjacent cells, in the order of [`Direction3::ALL`].
+    pub fn neighbors(self) -> [Vector3i; 6] {
+        Direction3::ALL.map(|direction| self + direction)
+    }
+
+    /// Any number of quarter turns about `axis` through the origin, matching [`Direction3::rotate()`].
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::{vector3i::Vector3i, axis3i::Axis3i};
+    /// assert_eq!(Vector3i::new(2, 1, 5).rotate_90(Axis3i::Z, 1), Vector3i::new(1, -2, 5));
+    /// ```
+    pub fn rotate_90(self, axis: Axis3i, quarter_turns: i32) -> Self {
+        (0..quarter_turns.rem_euclid(4))
+            .fold(self, |Vector3i { x, y, z }, _| match axis {
+                Axis3i::X => Vector3i::new(x, z, -y),
+                Axis3i::Y => Vector3i::new(-z, y, x),
+                Axis3i::Z => Vector3i::new(y, -x, z),
+            })
+    }
+
+    /// Like [`Self::rotate_90()`], but turning about `pivot` instead of the origin.
+    pub fn rotate_90_about(self, pivot: Vector3i, axis: Axis3i, quarter_turns: i32) -> Self {
+        (self - pivot).rotate_90(axis, quarter_turns) + pivot
+    }
+
+    /// The smallest step that repeats evenly to reach `self`, i.e. `self` divided by the GCD of its components.
+    /// Returns [`None`] for the zero vector, which has no direction.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::spacial::vector3i::Vector3i;
+    /// assert_eq!(Vector3i::new(3, 0, 0).primitive_step(), Some(Vector3i::new(1, 0, 0)));
+    /// assert_eq!(Vector3i::new(4, -6, 0).primitive_step(), Some(Vector3i::new(2, -3, 0)));
+    /// ```
+    pub fn primitive_step(self) -> Option<Vector3i> {
+        fn gcd(mut a: u32, mut b: u32) -> u32 {
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            a
+        }
+        let divisor = gcd(gcd(self.x.unsigned_abs(), self.y.unsigned_abs()), self.z.unsigned_abs());
+        (divisor != 0).then(|| Self {
+            x: self.x / divisor as i32,
+            y: self.y / divisor as i32,
+            z: self.z / divisor as i32,
+        })
+    }
+
+    #[cfg(feature = "raylib")]
+    /// Cast each component to [`f32`] as-is.
+    /// Unlike [`crate::render::RenderConfig::cell_to_world()`], this does not scale by the cell size.
+    pub fn as_vector3(self) -> Vector3 {
+        Vector3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+}
+
+impl fmt::Display for Vector3i {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
 }
 
 impl Neg for Vector3i {
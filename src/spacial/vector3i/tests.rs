@@ -0,0 +1,232 @@
+use super::*;
+
+#[cfg(feature = "raylib")]
+mod as_vector3 {
+    use super::*;
+
+    #[test]
+    fn test_cast() {
+        let v = Vector3i::new(3, -7, 0).as_vector3();
+        assert_eq!(v, Vector3::new(3.0, -7.0, 0.0), "cast should preserve integer values");
+    }
+
+    #[test]
+    fn test_unscaled() {
+        let v = Vector3i::new(1, 1, 1).as_vector3();
+        assert_eq!(v, Vector3::new(1.0, 1.0, 1.0), "cast should not apply cell size");
+    }
+}
+
+mod rem_euclid {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        let bounds = Vector3i::new(4, 4, 1);
+        assert_eq!(Vector3i::new(4, -1, 3).rem_euclid(bounds), Vector3i::new(0, 3, 0));
+        assert_eq!(Vector3i::new(2, 1, 0).rem_euclid(bounds), Vector3i::new(2, 1, 0), "in-range vectors should be unchanged");
+    }
+}
+
+mod primitive_step {
+    use super::*;
+
+    #[test]
+    fn test_axis_aligned() {
+        assert_eq!(Vector3i::new(3, 0, 0).primitive_step(), Some(Vector3i::new(1, 0, 0)));
+        assert_eq!(Vector3i::new(0, -5, 0).primitive_step(), Some(Vector3i::new(0, -1, 0)));
+        assert_eq!(Vector3i::new(0, 0, 1).primitive_step(), Some(Vector3i::new(0, 0, 1)));
+    }
+
+    #[test]
+    fn test_mixed() {
+        assert_eq!(Vector3i::new(4, -6, 0).primitive_step(), Some(Vector3i::new(2, -3, 0)));
+        assert_eq!(Vector3i::new(3, 3, 3).primitive_step(), Some(Vector3i::new(1, 1, 1)));
+        assert_eq!(Vector3i::new(2, 3, 5).primitive_step(), Some(Vector3i::new(2, 3, 5)), "coprime components should be unchanged");
+    }
+
+    #[test]
+    fn test_zero() {
+        assert_eq!(Vector3i::new(0, 0, 0).primitive_step(), None);
+    }
+}
+
+mod component {
+    use super::*;
+
+    #[test]
+    fn test_each_axis() {
+        let v = Vector3i::new(1, -2, 3);
+        assert_eq!(Axis3i::ALL.map(|axis| v.component(axis)), [1, -2, 3]);
+    }
+}
+
+mod manhattan_distance {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(Vector3i::new(0, 0, 0).manhattan_distance(Vector3i::new(1, 0, 0)), 1);
+        assert_eq!(Vector3i::new(1, -2, 3).manhattan_distance(Vector3i::new(-1, 2, 3)), 6);
+        assert_eq!(Vector3i::new(4, 4, 4).manhattan_distance(Vector3i::new(4, 4, 4)), 0);
+    }
+}
+
+mod manhattan_length {
+    use super::*;
+
+    #[test]
+    fn test_matches_distance_from_origin() {
+        let origin = Vector3i::new(0, 0, 0);
+        for v in [Vector3i::new(0, 0, 0), Vector3i::new(-3, 0, 0), Vector3i::new(1, -2, 3), Vector3i::new(i32::MIN, 0, 0)] {
+            assert_eq!(v.manhattan_length(), v.manhattan_distance(origin), "{v}");
+        }
+    }
+}
+
+mod step_toward {
+    use super::*;
+
+    #[test]
+    fn test_single_axis() {
+        let origin = Vector3i::new(0, 0, 0);
+        assert_eq!(origin.step_toward(Vector3i::new(5, 0, 0)), Some(Direction3::East));
+        assert_eq!(origin.step_toward(Vector3i::new(-5, 0, 0)), Some(Direction3::West));
+        assert_eq!(origin.step_toward(Vector3i::new(0, 1, 0)), Some(Direction3::North));
+        assert_eq!(origin.step_toward(Vector3i::new(0, -1, 0)), Some(Direction3::South));
+        assert_eq!(origin.step_toward(Vector3i::new(0, 0, 2)), Some(Direction3::Up));
+        assert_eq!(origin.step_toward(Vector3i::new(0, 0, -2)), Some(Direction3::Down));
+    }
+
+    #[test]
+    fn test_largest_component() {
+        let from = Vector3i::new(3, 3, 3);
+        assert_eq!(from.step_toward(Vector3i::new(4, 3, -1)), Some(Direction3::Down));
+        assert_eq!(from.step_toward(Vector3i::new(2, 7, 4)), Some(Direction3::North));
+    }
+
+    #[test]
+    fn test_ties() {
+        let origin = Vector3i::new(0, 0, 0);
+        assert_eq!(origin.step_toward(Vector3i::new(-2, 2, 0)), Some(Direction3::West), "X should win ties with Y");
+        assert_eq!(origin.step_toward(Vector3i::new(0, -2, 2)), Some(Direction3::South), "Y should win ties with Z");
+        assert_eq!(origin.step_toward(Vector3i::new(1, 1, 1)), Some(Direction3::East), "X should win three-way ties");
+    }
+
+    #[test]
+    fn test_arrived() {
+        let cell = Vector3i::new(-4, 2, 9);
+        assert_eq!(cell.step_toward(cell), None);
+    }
+}
+
+mod volume {
+    use super::*;
+
+    #[test]
+    fn test_boxes() {
+        assert_eq!(Vector3i::new(2, 3, 4).volume(), 24);
+        assert_eq!(Vector3i::new(8, 8, 1).volume(), 64, "a one-thick box is flat");
+        assert_eq!(Vector3i::new(-2, 3, 4).volume(), 24, "sign should not matter");
+        assert_eq!(Vector3i::new(5, 0, 5).volume(), 0);
+    }
+
+    #[test]
+    fn test_no_overflow() {
+        assert_eq!(Vector3i::new(i32::MAX, 2, 2).volume(), i32::MAX as i64 * 4);
+    }
+}
+
+mod packed {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let limit = (1 << (Vector3i::PACKED_BITS - 1)) - 1;
+        for cell in [
+            Vector3i::new(0, 0, 0),
+            Vector3i::new(1, -1, 2),
+            Vector3i::new(-5, 300, -70000),
+            Vector3i::new(limit, -limit - 1, limit),
+            Vector3i::new(-limit - 1, limit, -1),
+        ] {
+            let packed = cell.to_packed();
+            assert!(packed.is_some(), "{cell:?} should fit");
+            assert_eq!(Vector3i::from_packed(packed.unwrap()), cell);
+        }
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let limit = 1 << (Vector3i::PACKED_BITS - 1);
+        assert_eq!(Vector3i::new(limit, 0, 0).to_packed(), None);
+        assert_eq!(Vector3i::new(0, -limit - 1, 0).to_packed(), None);
+        assert_eq!(Vector3i::new(0, 0, i32::MAX).to_packed(), None);
+    }
+
+    #[test]
+    fn test_distinct() {
+        let a = Vector3i::new(1, 0, 0).to_packed();
+        let b = Vector3i::new(0, 1, 0).to_packed();
+        let c = Vector3i::new(0, 0, 1).to_packed();
+        assert!(a != b && b != c && a != c);
+    }
+}
+
+mod iter_box {
+    use super::*;
+
+    #[test]
+    fn test_count_is_volume() {
+        let lo = Vector3i::new(-2, 1, 3);
+        let hi = Vector3i::new(1, 4, 5);
+        assert_eq!(Vector3i::iter_box(lo, hi).count() as i64, (hi - lo + 1).volume());
+    }
+
+    #[test]
+    fn test_order() {
+        let cells: Vec<_> = Vector3i::iter_box(Vector3i::new(0, 0, 0), Vector3i::new(1, 1, 1)).collect();
+        let mut sorted = cells.clone();
+        sorted.sort_by_key(|cell| (cell.z, cell.y, cell.x));
+        assert_eq!(cells, sorted);
+        assert_eq!(cells.first(), Some(&Vector3i::new(0, 0, 0)));
+        assert_eq!(cells.last(), Some(&Vector3i::new(1, 1, 1)));
+    }
+
+    #[test]
+    fn test_inverted() {
+        assert_eq!(Vector3i::iter_box(Vector3i::new(0, 3, 0), Vector3i::new(2, 2, 2)).count(), 0);
+    }
+}
+
+mod rotate_90 {
+    use super::*;
+    use crate::spacial::axis3i::Axis3i;
+
+    #[test]
+    fn test_matches_direction() {
+        for axis in [Axis3i::X, Axis3i::Y, Axis3i::Z] {
+            for direction in Direction3::ALL {
+                for quarter_turns in -4..=4 {
+                    assert_eq!(
+                        Vector3i::from(direction).rotate_90(axis, quarter_turns),
+                        Vector3i::from(direction.rotate(axis, quarter_turns)),
+                        "{direction:?} turned {quarter_turns} about {axis:?}",
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_about_pivot_four_times() {
+        let pivot = Vector3i::new(3, -1, 2);
+        let shape = [Vector3i::new(4, -1, 2), Vector3i::new(5, 0, 2), Vector3i::new(3, 2, 7)];
+        for axis in [Axis3i::X, Axis3i::Y, Axis3i::Z] {
+            let turned = shape.map(|cell| (0..4).fold(cell, |cell, _| cell.rotate_90_about(pivot, axis, 1)));
+            assert_eq!(turned, shape);
+            assert_ne!(shape.map(|cell| cell.rotate_90_about(pivot, axis, 1)), shape);
+        }
+        assert_eq!(pivot.rotate_90_about(pivot, Axis3i::Y, 1), pivot, "the pivot should stay put");
+    }
+}
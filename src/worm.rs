@@ -1,16 +1,88 @@
 //! A worm.
 
-use crate::spacial::{vector3i::Vector3i, direction3::Direction3};
+use std::{collections::{HashSet, VecDeque}, hash::{Hash, Hasher}, sync::OnceLock};
+#[cfg(feature = "raylib")]
+use raylib::prelude::Vector3;
+use crate::{
+    spacial::{vector3i::Vector3i, direction3::Direction3, axis3i::Axis3i},
+    level::{Level, Object},
+    rules::{ruleset::Ruleset, word::noun::Noun},
+};
+#[cfg(feature = "raylib")]
+use crate::render::RenderConfig;
 pub mod segments;
 use segments::*;
+pub mod builder;
 
 #[cfg(test)]
 mod tests;
 
 /// A worm.
+///
+/// Two worms are equal when they have the same cells in the same order and face the same way.
+#[derive(Debug, Clone)]
 pub struct Worm {
     head_position: Vector3i,
     segments: Option<WormSegments>,
+    /// [`Self::facing()`] while tailless. Ignored otherwise, since the neck decides it.
+    tailless_facing: Direction3,
+    cells: CellCache,
+    crawl_history: CrawlHistory,
+}
+
+impl PartialEq for Worm {
+    fn eq(&self, other: &Self) -> bool {
+        self.head_position == other.head_position
+            && self.segments == other.segments
+            && self.facing() == other.facing()
+    }
+}
+
+impl Eq for Worm {}
+
+impl Hash for Worm {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.head_position.hash(state);
+        self.segments.hash(state);
+        self.facing().hash(state);
+    }
+}
+
+/// The set of cells a [`Worm`] occupies, built the first time it's needed and cleared whenever the worm changes.
+/// It only ever mirrors the worm's shape, so it is ignored when comparing and hashing worms.
+#[derive(Clone, Default)]
+struct CellCache(OnceLock<HashSet<Vector3i>>);
+
+impl std::fmt::Debug for CellCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("..")
+    }
+}
+
+/// Recent [`Worm::crawl()`]s, oldest first, for [`Worm::undo_crawl()`].
+/// Separate from [`crate::game_state::GameState::undo()`], which restores whole levels.
+#[derive(Debug, Clone, Default)]
+struct CrawlHistory {
+    /// The most steps kept; 0 turns recording off.
+    limit: usize,
+    steps: VecDeque<CrawlStep>,
+}
+
+/// Just enough about one [`Worm::crawl()`] to reverse it exactly.
+#[derive(Debug, Clone, Copy)]
+struct CrawlStep {
+    head_position: Vector3i,
+    tailless_facing: Direction3,
+    body: Option<BodyStep>,
+}
+
+/// How a crawl changed the segments of a worm with a body.
+#[derive(Debug, Clone, Copy)]
+enum BodyStep {
+    /// A new neck was added and this tail segment removed.
+    Forward { tail: Direction3 },
+    /// The worm backed up: this neck segment was removed and the tail extended.
+    Reversed { neck: Direction3 },
 }
 
 /// Calling [`Worm::try_lengthen()`] requires the worm to be at least 1 segment long so the tail can be extended in that direction.
@@ -25,19 +97,106 @@ impl std::fmt::Debug for LengthenTaillessError<'_> {
     }
 }
 
+impl std::fmt::Display for LengthenTaillessError<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+impl std::error::Error for LengthenTaillessError<'_> {}
+
 impl LengthenTaillessError<'_> {
     /// Resolve and consumes the [`LengthenTaillessError`] by specifying the direction in which the tail should grow.
     pub fn resolve(self, direction: Direction3) {
         _ = self.0.segments.insert(WormSegments::from([direction]));
+        self.0.reshaped();
+    }
+}
+
+/// [`Worm::from_str()`] found a character that isn't a direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WormParseError(pub char);
+
+impl std::fmt::Display for WormParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid character: '{}'", self.0)
     }
 }
 
+impl std::error::Error for WormParseError {}
+
+/// The reason [`Worm::try_crawl_in()`] could not move the worm.
+/// Each variant holds the cell the worm would have needed to enter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrawlBlocked {
+    /// The cell is outside the level's bounds.
+    OutOfBounds(Vector3i),
+    /// The cell is a wall.
+    Solid(Vector3i),
+    /// The cell is occupied by another worm or a text block.
+    Occupied(Vector3i),
+    /// The cell is occupied by another part of the same worm.
+    SelfIntersecting(Vector3i),
+}
+
+impl std::fmt::Display for CrawlBlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfBounds(cell) => write!(f, "{cell} is out of bounds"),
+            Self::Solid(cell) => write!(f, "{cell} is solid"),
+            Self::Occupied(cell) => write!(f, "{cell} is occupied"),
+            Self::SelfIntersecting(cell) => write!(f, "{cell} is already part of the worm"),
+        }
+    }
+}
+
+impl std::error::Error for CrawlBlocked {}
+
+/// What changed when [`Worm::try_crawl_in()`] moved the worm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrawlOutcome {
+    /// The cell the tail just left, which the head didn't move into.
+    /// [`None`] if the worm [reversed](Worm::is_reversal()) or chased its own tail, so no cell was left behind for the head's new cell.
+    pub vacated: Option<Vector3i>,
+}
+
+/// The reason [`Worm::try_lengthen_in()`] could not grow the worm.
+/// Each variant but [`Self::Tailless`] holds the cell the new tail would have needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrowBlocked {
+    /// The worm has no tail to extend, as in [`LengthenTaillessError`].
+    Tailless,
+    /// The cell is outside the level's bounds.
+    OutOfBounds(Vector3i),
+    /// The cell is a wall.
+    Solid(Vector3i),
+    /// The cell is occupied by another worm or a text block.
+    Occupied(Vector3i),
+    /// The cell is occupied by another part of the same worm.
+    SelfIntersecting(Vector3i),
+}
+
+impl std::fmt::Display for GrowBlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tailless => write!(f, "missing tail to lengthen"),
+            Self::OutOfBounds(cell) => write!(f, "{cell} is out of bounds"),
+            Self::Solid(cell) => write!(f, "{cell} is solid"),
+            Self::Occupied(cell) => write!(f, "{cell} is occupied"),
+            Self::SelfIntersecting(cell) => write!(f, "{cell} is already part of the worm"),
+        }
+    }
+}
+
+impl std::error::Error for GrowBlocked {}
+
 impl Worm {
     /// Construct a worm from head and segments.
     /// Each segment directs where the tail will go.
     ///
     /// Example:
     /// ```no_run
+    /// # use puzzle_game_rs::{worm::Worm, spacial::{vector3i::Vector3i, direction3::Direction3}};
     /// use Direction3::*;
     /// /********
     ///  * o--. *
@@ -61,6 +220,9 @@ impl Worm {
         Self {
             head_position,
             segments,
+            tailless_facing: Direction3::East,
+            cells: CellCache::default(),
+            crawl_history: CrawlHistory::default(),
         }
     }
 
@@ -84,6 +246,9 @@ impl Worm {
                 head_position,
                 segments: segments
                     .map(|inner| inner.unwrap()),
+                tailless_facing: Direction3::East,
+                cells: CellCache::default(),
+                crawl_history: CrawlHistory::default(),
             })
         }
     }
@@ -93,6 +258,7 @@ impl Worm {
     /// 
     /// Example:
     /// ```
+    /// # use puzzle_game_rs::{worm::Worm, spacial::{vector3i::Vector3i, direction3::Direction3}};
     /// use Direction3::*;
     /// let head_position = Vector3i::new(5, 3, 8);
     /// /******************
@@ -100,7 +266,7 @@ impl Worm {
     ///  * o------:--:==- *
     ///  *      --'       *
     ///  ******************/
-    /// let worm1 = Worm::from_str(head_position, ">>>^>v>o<<xv<");
+    /// let worm1 = Worm::from_str(head_position, ">>>^>v>o<<xv<").unwrap();
     /// let worm2 = Worm::new(head_position, [
     ///     East,
     ///     East,
@@ -122,21 +288,69 @@ impl Worm {
     ///     assert_eq!(seg1, seg2);
     /// }
     /// ```
-    pub fn from_str(head_position: Vector3i, segments: &str) -> Result<Self, impl '_ + std::fmt::Debug> {
-        Self::try_new(head_position, segments
+    pub fn from_str(head_position: Vector3i, segments: &str) -> Result<Self, WormParseError> {
+        let segments = segments
             .chars()
             .map(|ch|
                 match ch {
-                    '<' => Ok(Direction3::East),
-                    '>' => Ok(Direction3::West),
+                    '>' => Ok(Direction3::East),
+                    '<' => Ok(Direction3::West),
                     '^' => Ok(Direction3::North),
                     'v' => Ok(Direction3::South),
                     'o' => Ok(Direction3::Down),
                     'x' => Ok(Direction3::Up),
-                    _ => Err(format!("invalid character: '{ch}'"))
+                    _ => Err(WormParseError(ch))
                 }
             )
-        )
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(head_position, segments))
+    }
+
+    /// The direction of each segment from the one before it, starting at the head.
+    /// Empty for a tailless worm.
+    pub fn segment_directions(&self) -> impl '_ + Iterator<Item = Direction3> {
+        self.segments().iter()
+    }
+
+    /// Read-only access to the segments, with their count, ends, and indexing as well as [iteration](Self::segment_directions()).
+    /// Empty for a tailless worm.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::{spacial::{direction3::Direction3, vector3i::Vector3i}, worm::Worm};
+    /// let worm = Worm::from_str(Vector3i::new(0, 0, 0), "<<v").unwrap();
+    /// let segments = worm.segments();
+    /// assert_eq!(segments.len(), 3);
+    /// assert_eq!(segments.tail_direction(), Some(Direction3::South));
+    /// assert_eq!(segments[1], Direction3::West);
+    /// ```
+    pub fn segments(&self) -> SegmentsView<'_> {
+        SegmentsView::new(self.segments.as_ref())
+    }
+
+    /// The same directions as [`Self::segment_directions()`], borrowed as a single slice.
+    ///
+    /// Segments are stored in a ring buffer that may wrap around, so this rearranges them in memory first,
+    /// hence `&mut self`. The worm itself is unchanged, and later calls are free until it crawls again.
+    pub fn segment_directions_contiguous(&mut self) -> &[Direction3] {
+        match &mut self.segments {
+            Some(segments) => segments.make_contiguous(),
+            None => &[],
+        }
+    }
+
+    /// The segments as a direction string, in the format read by [`Self::from_str()`].
+    pub fn to_segments_string(&self) -> String {
+        self.segment_directions()
+            .map(|direction| match direction {
+                Direction3::East => '>',
+                Direction3::West => '<',
+                Direction3::North => '^',
+                Direction3::South => 'v',
+                Direction3::Down => 'o',
+                Direction3::Up => 'x',
+            })
+            .collect()
     }
 
     /// The worm is just a head with no segments?
@@ -150,6 +364,46 @@ impl Worm {
         self.head_position
     }
 
+    /// The grid position of the segment just behind the head, or [`None`] if the worm is tailless.
+    pub fn neck_position(&self) -> Option<Vector3i> {
+        self.segments
+            .as_ref()
+            .map(|segments| self.head_position + segments.head_direction())
+    }
+
+    /// The way the head points: away from the neck, or for a tailless worm, the way it last crawled.
+    /// A tailless worm that has never crawled faces [`Direction3::East`].
+    pub fn facing(&self) -> Direction3 {
+        match &self.segments {
+            Some(segments) => -segments.head_direction(),
+            None => self.tailless_facing,
+        }
+    }
+
+    /// Where the head is and which way it is [facing](Self::facing), e.g. to aim with [`Level::raycast()`](crate::level::Level::raycast).
+    pub fn head_ray(&self) -> (Vector3i, Direction3) {
+        (self.head_position, self.facing())
+    }
+
+    /// The cell the head would move into by crawling in `direction`.
+    pub fn cell_ahead(&self, direction: Direction3) -> Vector3i {
+        self.head_position + direction
+    }
+
+    /// The worm forms a closed ring: its tail is orthogonally adjacent to its head and its body never crosses itself.
+    ///
+    /// A tailless worm is never a loop, and neither is one with a single segment (its tail touches its head, but encloses nothing).
+    /// On a grid, the smallest possible loop is 4 cells.
+    pub fn is_loop(&self) -> bool {
+        if self.num_segments() < 4 {
+            return false;
+        }
+        let tail_position = self.segment_positions().last().unwrap();
+        let mut visited = HashSet::new();
+        tail_position.manhattan_distance(self.head_position) == 1
+            && self.segment_positions().all(|cell| visited.insert(cell))
+    }
+
     /// Number of elements returned by [`Self::segment_positions()`].
     pub fn num_segments(&self) -> usize {
         match &self.segments {
@@ -158,11 +412,46 @@ impl Worm {
         }
     }
 
+    /// Cut the worm in two before the segment at `index` of [`Self::segment_positions()`].
+    /// The first piece keeps the head and the segments before `index`;
+    /// the second piece, if `index` isn't past the tail, starts with the segment at `index` as its head.
+    ///
+    /// Panics if `index` is 0 or greater than [`Self::num_segments()`].
+    pub fn split_at(&self, index: usize) -> (Worm, Option<Worm>) {
+        assert!((1..=self.num_segments()).contains(&index), "split index out of range");
+        let directions: Vec<Direction3> = self.segments
+            .iter()
+            .flat_map(|segments| segments.iter().copied())
+            .collect();
+        let (headward, tailward) = directions.split_at(index - 1);
+        let head_piece = Worm::new(self.head_position, headward.iter().copied());
+        let tail_piece = tailward
+            .split_first()
+            .map(|(&link, rest)| {
+                let new_head = self.segment_positions().nth(index - 1).unwrap() + link;
+                Worm::new(new_head, rest.iter().copied())
+            });
+        (head_piece, tail_piece)
+    }
+
+    /// The number of steps along the body between the segments at indices `a` and `b` of [`Self::segment_positions()`].
+    /// Unlike the distance between their cells, this counts every segment in between, even where the worm folds back on itself.
+    ///
+    /// Panics if either index is not less than [`Self::num_segments()`].
+    pub fn body_distance(&self, a: usize, b: usize) -> usize {
+        let num_segments = self.num_segments();
+        assert!(a < num_segments && b < num_segments, "segment index out of range");
+        a.abs_diff(b)
+    }
+
     /// Increases the length of the worm in the direction of its tail.
     /// Does not have awareness of the level geometry.
     ///
     /// Example:
     /// ```
+    /// # use puzzle_game_rs::{worm::Worm, spacial::{vector3i::Vector3i, direction3::Direction3}};
+    /// # let mut my_worm = Worm::new(Vector3i::new(0, 0, 0), []);
+    /// # let prompt_direction = || Direction3::North;
     /// my_worm
     ///     .try_lengthen()
     ///     .unwrap_or_else(|err| {
@@ -175,30 +464,394 @@ impl Worm {
             Some(segments) => {
                 let tail_direction = segments.tail_direction();
                 segments.push_tail(tail_direction);
+                self.reshaped();
                 Ok(())
             },
             None => Err(LengthenTaillessError(self)),
         }
     }
 
+    /// Lengthens the worm like [`Self::try_lengthen()`],
+    /// but only if the new tail cell is free in `level`.
+    /// The worm is left unchanged when blocked.
+    ///
+    /// As with [`Self::try_crawl_in()`], walls and text only block the worm if they [interact](Ruleset::interacts) with it under `rules`.
+    pub fn try_lengthen_in(&mut self, level: &Level, rules: &Ruleset) -> Result<(), GrowBlocked> {
+        let Some(segments) = &self.segments else {
+            return Err(GrowBlocked::Tailless);
+        };
+        let cell = self.segment_positions().last().unwrap() + segments.tail_direction();
+        if self.occupies(cell) {
+            return Err(GrowBlocked::SelfIntersecting(cell));
+        }
+        if !level.in_bounds(cell) {
+            return Err(GrowBlocked::OutOfBounds(cell));
+        }
+        if level.is_solid(cell) && rules.interacts(Noun::WORM, Noun::WALL) {
+            return Err(GrowBlocked::Solid(cell));
+        }
+        let text_blocks = level.text_at(cell).is_some() && rules.interacts(Noun::WORM, Noun::TEXT);
+        if text_blocks || level.worm_at(cell).is_some() {
+            return Err(GrowBlocked::Occupied(cell));
+        }
+        self.try_lengthen().expect("the worm has a tail");
+        Ok(())
+    }
+
+    /// [`Self::shrink_by()`] as much as needed for the worm to take up at most `max_cells` cells.
+    /// Trimming to 1 (or 0, since the head is never removed) leaves the worm tailless.
+    pub fn trim_to(&mut self, max_cells: usize) {
+        self.shrink_by(self.num_segments().saturating_sub(max_cells));
+    }
+
+    /// Lengthen the worm by `n` segments as if by [`Self::try_lengthen()`].
+    /// A tailless worm grows its first segment behind the way it's [facing](Self::facing()), so it keeps facing that way.
+    pub fn grow_by(&mut self, n: usize) {
+        for _ in 0..n {
+            let behind = -self.facing();
+            self.try_lengthen().unwrap_or_else(|err| err.resolve(behind));
+        }
+    }
+
+    /// Remove up to `n` segments from the tail end.
+    /// Shrinking by more than the worm has leaves it tailless; the head is never removed.
+    pub fn shrink_by(&mut self, n: usize) {
+        self.reshaped();
+        // Keep facing the same way once the neck is gone
+        self.tailless_facing = self.facing();
+        for _ in 0..n {
+            let Some(segments) = self.segments.take() else {
+                break;
+            };
+            self.segments = segments.pop_tail().updated_segments;
+        }
+    }
+
     /// Pulls the worm's head in the requested direction without changing the worm's length.
     /// Does not have awareness of the level geometry.
     pub fn crawl(&mut self, crawl_direction: Direction3) {
+        self.cells = CellCache::default();
+        let mut step = CrawlStep {
+            head_position: self.head_position,
+            tailless_facing: self.tailless_facing,
+            body: None,
+        };
         self.head_position += crawl_direction;
+        self.tailless_facing = crawl_direction;
         if !self.is_tailless() {
             let mut segments = std::mem::take(&mut self.segments).unwrap();
             let new_head_direction = -crawl_direction;
             let current_head_direction = segments.head_direction();
-            self.segments = if -current_head_direction != new_head_direction {
+            let current_tail_direction = segments.tail_direction();
+            let current_len = segments.len();
+            let is_reversing = current_head_direction.is_opposite(new_head_direction);
+            self.segments = if !is_reversing {
                 segments.push_head(new_head_direction);
-                segments.pop_tail().updated_segments
+                let popped = segments.pop_tail();
+                step.body = Some(BodyStep::Forward { tail: popped.old_direction });
+                popped.updated_segments
             } else {
                 // reversing
-                let current_tail_direction = segments.tail_direction();
                 segments.push_tail(current_tail_direction);
-                segments.pop_head().updated_segments
+                let popped = segments.pop_head();
+                step.body = Some(BodyStep::Reversed { neck: popped.old_direction });
+                popped.updated_segments
             };
+            if let Some(segments) = &self.segments {
+                debug_assert_eq!(segments.len(), current_len, "crawling should never change the worm's length");
+                if is_reversing {
+                    debug_assert_eq!(segments.tail_direction(), current_tail_direction, "reversing should extend the tail straight back");
+                } else {
+                    debug_assert_eq!(segments.head_direction(), new_head_direction, "the neck should be where the head just was");
+                }
+            } else {
+                debug_assert!(false, "crawling should never remove the worm's segments");
+            }
+        }
+        let history = &mut self.crawl_history;
+        if history.limit != 0 {
+            if history.steps.len() == history.limit {
+                history.steps.pop_front();
+            }
+            history.steps.push_back(step);
+        }
+    }
+
+    /// Remember up to `limit` of the most recent [`Self::crawl()`]s so they can be reverted with [`Self::undo_crawl()`].
+    /// Off (0) by default; turning it off forgets any that were remembered.
+    ///
+    /// This is per-worm rewind, independent of [`crate::game_state::GameState::undo()`].
+    /// Any other change to the worm's shape or position, like [`Self::translate()`] or [`Self::shrink_by()`], forgets every crawl before it.
+    pub fn set_crawl_history_limit(&mut self, limit: usize) {
+        let history = &mut self.crawl_history;
+        history.limit = limit;
+        while history.steps.len() > limit {
+            history.steps.pop_front();
+        }
+    }
+
+    /// Revert the most recent remembered [`Self::crawl()`] exactly, head, body, and facing.
+    /// Returns false if there are none; see [`Self::set_crawl_history_limit()`].
+    pub fn undo_crawl(&mut self) -> bool {
+        let Some(step) = self.crawl_history.steps.pop_back() else {
+            return false;
+        };
+        self.cells = CellCache::default();
+        self.head_position = step.head_position;
+        self.tailless_facing = step.tailless_facing;
+        if let Some(body) = step.body {
+            let segments = self.segments.take().expect("crawling should never remove the worm's segments");
+            self.segments = Some(match body {
+                BodyStep::Forward { tail } => match segments.pop_head().updated_segments {
+                    Some(mut segments) => {
+                        segments.push_tail(tail);
+                        segments
+                    },
+                    None => WormSegments::from(tail),
+                },
+                BodyStep::Reversed { neck } => match segments.pop_tail().updated_segments {
+                    Some(mut segments) => {
+                        segments.push_head(neck);
+                        segments
+                    },
+                    None => WormSegments::from(neck),
+                },
+            });
+        }
+        true
+    }
+
+    /// Clear everything that depends on the worm's old shape: the cached cells,
+    /// and the crawl history, which can't be reverted past a change that isn't a crawl.
+    fn reshaped(&mut self) {
+        self.cells = CellCache::default();
+        self.crawl_history.steps.clear();
+    }
+
+    /// Crawling in `direction` would turn the worm back on itself, so [`Self::crawl()`] would reverse it?
+    /// Always false for a tailless worm, which has no body to back into.
+    pub fn is_reversal(&self, direction: Direction3) -> bool {
+        !self.is_tailless() && self.facing().is_opposite(direction)
+    }
+
+    /// Pushes the worm's head in the requested direction like [`Self::crawl()`], but without moving the tail,
+    /// so the worm grows by one segment and its body traces the path the head took.
+    /// Does not have awareness of the level geometry.
+    pub fn crawl_growing(&mut self, crawl_direction: Direction3) {
+        self.reshaped();
+        self.head_position += crawl_direction;
+        match &mut self.segments {
+            Some(segments) => segments.push_head(-crawl_direction),
+            None => self.segments = Some(WormSegments::from([-crawl_direction])),
+        }
+    }
+
+    /// Pulls the worm's head in the requested direction like [`Self::crawl()`],
+    /// but only if every cell the worm would occupy afterwards is free in `level`.
+    /// The worm is left unchanged when blocked.
+    ///
+    /// Cells currently occupied by this worm are not considered blocked by other worms,
+    /// so this can be called on a copy of a worm that is still in the level.
+    /// Walls and text only block the worm if they [interact](Ruleset::interacts) with it under `rules`.
+    ///
+    /// Example:
+    /// ```
+    /// # use puzzle_game_rs::{level::Level, rules::ruleset::Ruleset, spacial::{direction3::Direction3, vector3i::Vector3i}, worm::Worm};
+    /// let level = Level::new(Vector3i::new(4, 1, 1));
+    /// let mut worm = Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap();
+    /// let outcome = worm.try_crawl_in(Direction3::East, &level, &Ruleset::new()).unwrap();
+    /// assert_eq!(outcome.vacated, Some(Vector3i::new(0, 0, 0)));
+    /// ```
+    pub fn try_crawl_in(&mut self, crawl_direction: Direction3, level: &Level, rules: &Ruleset) -> Result<CrawlOutcome, CrawlBlocked> {
+        let mut moved = self.clone();
+        moved.crawl(crawl_direction);
+        let mut visited = HashSet::new();
+        for cell in moved.segment_positions() {
+            if !visited.insert(cell) {
+                return Err(CrawlBlocked::SelfIntersecting(cell));
+            }
+            if !level.in_bounds(cell) {
+                return Err(CrawlBlocked::OutOfBounds(cell));
+            }
+            for &object in level.objects_at(cell) {
+                match object {
+                    _ if !rules.interacts(Noun::WORM, object.noun()) => {},
+                    Object::Goal => {},
+                    Object::Wall => return Err(CrawlBlocked::Solid(cell)),
+                    Object::Worm(_) if self.occupies(cell) => {},
+                    Object::Text(_) | Object::Worm(_) => return Err(CrawlBlocked::Occupied(cell)),
+                }
+            }
+        }
+        let vacated = Some(self.segment_positions().last().expect("a worm always has a head"))
+            .filter(|&tail| !self.is_reversal(crawl_direction) && !moved.occupies(tail));
+        *self = moved;
+        Ok(CrawlOutcome { vacated })
+    }
+
+    /// The smallest box containing every segment, as its inclusive `(min, max)` corners.
+    pub fn bounding_box(&self) -> (Vector3i, Vector3i) {
+        self.segment_positions()
+            .fold((self.head_position, self.head_position), |(min, max), cell| (min.component_min(cell), max.component_max(cell)))
+    }
+
+    /// The average of every segment's cell, e.g. for framing the worm or aiming at its middle rather than its head.
+    /// Each component is rounded to the nearest integer, with halves rounded up (towards positive infinity).
+    /// A tailless worm's centroid is its head.
+    pub fn centroid(&self) -> Vector3i {
+        let (mut sum, mut count) = ([0i64; 3], 0i64);
+        for cell in self.segment_positions() {
+            sum[0] += cell.x as i64;
+            sum[1] += cell.y as i64;
+            sum[2] += cell.z as i64;
+            count += 1;
         }
+        let [x, y, z] = sum.map(|sum| (2 * sum + count).div_euclid(2 * count) as i32);
+        Vector3i::new(x, y, z)
+    }
+
+    /// [`Self::translate()`] the worm as little as possible so its [`Self::bounding_box()`] lies within the inclusive box from `lo` to `hi`.
+    /// Returns whether the worm had to move.
+    ///
+    /// A worm too big to fit in the box is left where it is, and false is returned.
+    pub fn clamp_to_bounds(&mut self, lo: Vector3i, hi: Vector3i) -> bool {
+        let (min, max) = self.bounding_box();
+        let size = max - min;
+        let room = hi - lo;
+        if size.x > room.x || size.y > room.y || size.z > room.z {
+            return false;
+        }
+        // Push back inside from whichever side the worm pokes out of; it can't be both
+        let delta = (lo - min).component_max(Vector3i::new(0, 0, 0))
+            + (hi - max).component_min(Vector3i::new(0, 0, 0));
+        self.translate(delta);
+        delta != Vector3i::new(0, 0, 0)
+    }
+
+    /// Crawls in `direction` with [`Self::try_crawl_in()`] over and over until blocked.
+    /// Returns how many cells the head moved, which is 0 if the very first step is blocked.
+    pub fn slide(&mut self, direction: Direction3, level: &Level, rules: &Ruleset) -> usize {
+        let mut distance = 0;
+        while self.try_crawl_in(direction, level, rules).is_ok() {
+            distance += 1;
+        }
+        distance
+    }
+
+    /// Moves the whole worm by `delta` without changing its shape.
+    /// Segments are stored relative to the head, so only the head actually moves.
+    /// Does not have awareness of the level geometry.
+    pub fn translate(&mut self, delta: Vector3i) {
+        self.reshaped();
+        self.head_position += delta;
+    }
+
+    /// Pulls the worm's head in the requested direction like [`Self::crawl()`],
+    /// wrapping the head around to the opposite side of `bounds` (usually [`crate::level::Level::bounds()`]) when it leaves.
+    ///
+    /// The segments are relative, so the body follows the head across the edge.
+    /// Use [`Self::segment_positions_wrapping()`] to get the body's positions inside the bounds.
+    /// A dimension that is 1 cell wide wraps onto itself.
+    ///
+    /// Panics if any component of `bounds` is zero.
+    pub fn crawl_wrapping(&mut self, crawl_direction: Direction3, bounds: Vector3i) {
+        self.crawl(crawl_direction);
+        self.cells = CellCache::default();
+        self.head_position = self.head_position.rem_euclid(bounds);
+    }
+
+    /// Like [`Self::segment_positions()`], but each position is wrapped into `bounds`.
+    /// For worms moved with [`Self::crawl_wrapping()`].
+    ///
+    /// Panics if any component of `bounds` is zero.
+    pub fn segment_positions_wrapping<'worm>(&'worm self, bounds: Vector3i) -> impl 'worm + Iterator<Item = Vector3i> {
+        self.segment_positions()
+            .map(move |position| position.rem_euclid(bounds))
+    }
+
+    /// Spins the whole worm about `axis` through its head, like [`Direction3::rotate()`].
+    /// The head stays in place while the body swings around it.
+    pub fn rotate(&mut self, axis: Axis3i, quarter_turns: i32) {
+        self.reshaped();
+        self.tailless_facing = self.tailless_facing.rotate(axis, quarter_turns);
+        if let Some(segments) = &mut self.segments {
+            for direction in segments.iter_mut() {
+                *direction = direction.rotate(axis, quarter_turns);
+            }
+        }
+    }
+
+    /// Reflects the whole worm across the plane through its head perpendicular to `axis`, like [`Direction3::mirror()`].
+    /// The head stays in place while the body flips over.
+    pub fn mirror(&mut self, axis: Axis3i) {
+        self.reshaped();
+        self.tailless_facing = self.tailless_facing.mirror(axis);
+        if let Some(segments) = &mut self.segments {
+            for direction in segments.iter_mut() {
+                *direction = direction.mirror(axis);
+            }
+        }
+    }
+
+    /// Swaps the head and tail in place, so the worm occupies the same cells but faces the other way.
+    /// A tailless worm just turns around.
+    pub fn reverse(&mut self) {
+        self.reshaped();
+        if self.is_tailless() {
+            self.tailless_facing = -self.tailless_facing;
+            return;
+        }
+        let tail_position = self.segment_positions_rev().next().unwrap();
+        let directions: Vec<Direction3> = self.segments
+            .iter()
+            .flat_map(|segments| segments.iter().rev())
+            .map(|&direction| -direction)
+            .collect();
+        let crawl_history = std::mem::take(&mut self.crawl_history);
+        *self = Worm::new(tail_position, directions);
+        self.crawl_history = crawl_history;
+    }
+
+    /// Both worms occupy exactly the same cells, regardless of which end is the head.
+    /// Unlike `==`, a worm and its [reverse](Self::reverse()) are the same shape.
+    pub fn same_shape(&self, other: &Worm) -> bool {
+        self.segment_positions().collect::<HashSet<_>>() == other.segment_positions().collect::<HashSet<_>>()
+    }
+
+    /// Like [`Self::segment_positions()`], but starting at the tail and ending at the head.
+    pub fn segment_positions_rev<'worm>(&'worm self) -> impl 'worm + Iterator<Item = Vector3i> {
+        let directions = || self.segments
+            .iter()
+            .flat_map(|segments| segments.iter());
+        let tail_position = directions()
+            .fold(self.head_position, |position, &direction| position + direction);
+        Some(tail_position)
+            .into_iter()
+            .chain(directions()
+                .rev()
+                .scan(tail_position, |tailward_segment_position, &segment_direction| {
+                    *tailward_segment_position -= segment_direction;
+                    Some(*tailward_segment_position)
+                })
+            )
+    }
+
+    /// Every cell the worm occupies.
+    /// Built on first use and kept until the worm changes, so repeated occupancy checks don't walk the body each time.
+    pub fn occupied_cells(&self) -> &HashSet<Vector3i> {
+        self.cells.0.get_or_init(|| self.segment_positions().collect())
+    }
+
+    /// Any part of the worm is in the cell?
+    pub fn occupies(&self, cell: Vector3i) -> bool {
+        self.occupied_cells().contains(&cell)
+    }
+
+    /// The two worms share any cell?
+    pub fn overlaps(&self, other: &Worm) -> bool {
+        let (shorter, longer) = if self.num_segments() <= other.num_segments() { (self, other) } else { (other, self) };
+        let cells = longer.occupied_cells();
+        shorter.segment_positions().any(|cell| cells.contains(&cell))
     }
 
     /// Create an iterator over the worm's segments' world positions.
@@ -206,7 +859,10 @@ impl Worm {
     ///
     /// Example
     /// ```no_run
+    /// # use puzzle_game_rs::{worm::Worm, spacial::vector3i::Vector3i};
+    /// # let worm = Worm::new(Vector3i::new(0, 0, 0), []);
     /// let worm: Worm = // ...
+    /// #   worm;
     /// let mut it = worm.segment_positions();
     /// let head_position = it.next().unwrap();
     /// let tail_position = it.last();
@@ -227,4 +883,26 @@ impl Worm {
                 .flatten()
             )
     }
+
+    /// Replace the contents of `buf` with [`Self::segment_positions()`].
+    ///
+    /// This is the allocation-free path for hot loops: reuse one buffer across calls
+    /// and it only grows when a worm is longer than any it has held before.
+    pub fn collect_positions_into(&self, buf: &mut Vec<Vector3i>) {
+        buf.clear();
+        buf.extend(self.segment_positions());
+    }
+
+    /// The world position of the head, as laid out by `config`.
+    #[cfg(feature = "raylib")]
+    pub fn head_world_position(&self, config: &RenderConfig) -> Vector3 {
+        config.cell_to_world(self.head_position)
+    }
+
+    /// [`Self::segment_positions()`] converted to world positions, as laid out by `config`.
+    #[cfg(feature = "raylib")]
+    pub fn segment_world_positions<'worm>(&'worm self, config: &'worm RenderConfig) -> impl 'worm + Iterator<Item = Vector3> {
+        self.segment_positions()
+            .map(|position| config.cell_to_world(position))
+    }
 }
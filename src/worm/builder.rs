@@ -0,0 +1,48 @@
+//! Incremental construction of a [`Worm`].
+
+use crate::spacial::{vector3i::Vector3i, direction3::Direction3};
+use super::Worm;
+
+/// Builds a [`Worm`] one segment at a time.
+/// The head position is required up front, so any builder can produce a worm.
+///
+/// Example:
+/// ```
+/// # use puzzle_game_rs::{worm::builder::WormBuilder, spacial::{vector3i::Vector3i, direction3::Direction3::*}};
+/// let mut builder = WormBuilder::head(Vector3i::new(0, 0, 0))
+///     .segments([East, East]);
+/// builder.extend([South]);
+/// let worm = builder.build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct WormBuilder {
+    head_position: Vector3i,
+    segments: Vec<Direction3>,
+}
+
+impl WormBuilder {
+    /// Start building a worm whose head is at `head_position`.
+    pub fn head(head_position: Vector3i) -> Self {
+        Self {
+            head_position,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Append segments, in order from the head towards the tail.
+    pub fn segments(mut self, segments: impl IntoIterator<Item = Direction3>) -> Self {
+        self.segments.extend(segments);
+        self
+    }
+
+    /// Finish building.
+    pub fn build(self) -> Worm {
+        Worm::new(self.head_position, self.segments)
+    }
+}
+
+impl Extend<Direction3> for WormBuilder {
+    fn extend<T: IntoIterator<Item = Direction3>>(&mut self, iter: T) {
+        self.segments.extend(iter);
+    }
+}
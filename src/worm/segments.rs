@@ -1,11 +1,11 @@
 //! Container adapter for [`crate::worm::Worm`]'s segments.
 //! Code outside of this module should not care what container [`WormSegments`] uses.
 
-use std::collections::VecDeque;
+use std::{collections::{VecDeque, vec_deque}, ops::Index};
 use crate::spacial::direction3::Direction3;
 
 /// A collection of segments.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct WormSegments(VecDeque<Direction3>);
 
 pub(super) struct PopResult {
@@ -91,4 +91,100 @@ impl WormSegments {
     pub(super) fn iter(&self) -> std::collections::vec_deque::Iter<'_, Direction3> {
         self.0.iter()
     }
+
+    pub(super) fn make_contiguous(&mut self) -> &[Direction3] {
+        self.0.make_contiguous()
+    }
+
+    pub(super) fn iter_mut(&mut self) -> std::collections::vec_deque::IterMut<'_, Direction3> {
+        self.0.iter_mut()
+    }
+}
+
+/// Read-only access to a worm's segments, from [`crate::worm::Worm::segments()`].
+/// Directions are as in [`crate::worm::Worm::segment_directions()`]: each is from the segment before it, starting at the head.
+///
+/// A tailless worm's view is empty.
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentsView<'a>(Option<&'a WormSegments>);
+
+impl<'a> SegmentsView<'a> {
+    pub(super) fn new(segments: Option<&'a WormSegments>) -> Self {
+        Self(segments)
+    }
+
+    /// The number of segments, not counting the head.
+    pub fn len(&self) -> usize {
+        self.0.map_or(0, WormSegments::len)
+    }
+
+    /// The worm is tailless?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Like [`WormSegments::head_direction()`], or [`None`] if the view is empty.
+    pub fn head_direction(&self) -> Option<Direction3> {
+        self.0.map(WormSegments::head_direction)
+    }
+
+    /// Like [`WormSegments::tail_direction()`], or [`None`] if the view is empty.
+    pub fn tail_direction(&self) -> Option<Direction3> {
+        self.0.map(WormSegments::tail_direction)
+    }
+
+    /// The direction at `index`, counting from the head, or [`None`] if out of range.
+    pub fn get(&self, index: usize) -> Option<Direction3> {
+        self.0.and_then(|segments| segments.0.get(index)).copied()
+    }
+
+    /// Every direction, from the head to the tail.
+    pub fn iter(&self) -> SegmentsIter<'a> {
+        SegmentsIter(self.0.map(|segments| segments.0.iter()))
+    }
 }
+
+impl Index<usize> for SegmentsView<'_> {
+    type Output = Direction3;
+
+    /// Panics if `index` is out of range, including for any index into an empty view.
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.0 {
+            Some(segments) => &segments.0[index],
+            None => panic!("index {index} out of range for a tailless worm's segments"),
+        }
+    }
+}
+
+impl<'a> IntoIterator for SegmentsView<'a> {
+    type Item = Direction3;
+    type IntoIter = SegmentsIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over a [`SegmentsView`], from the head to the tail.
+#[derive(Debug, Clone)]
+pub struct SegmentsIter<'a>(Option<vec_deque::Iter<'a, Direction3>>);
+
+impl Iterator for SegmentsIter<'_> {
+    type Item = Direction3;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.as_mut()?.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.as_ref().map_or((0, Some(0)), Iterator::size_hint)
+    }
+}
+
+impl DoubleEndedIterator for SegmentsIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.as_mut()?.next_back().copied()
+    }
+}
+
+impl ExactSizeIterator for SegmentsIter<'_> {}
@@ -1,5 +1,13 @@
 use super::*;
 
+/// Every pair of consecutive segments is exactly one orthogonal step apart.
+fn assert_contiguous(worm: &Worm) {
+    let positions: Vec<_> = worm.segment_positions().collect();
+    for pair in positions.windows(2) {
+        assert_eq!((pair[1] - pair[0]).manhattan_length(), 1, "segments should be adjacent: {positions:?}");
+    }
+}
+
 #[cfg(test)]
 mod from_str {
     use super::*;
@@ -15,12 +23,21 @@ mod from_str {
          ******************/
         let worm1 = Worm::from_str(head_position, ">>>^>v>o<<xv<").unwrap();
         let worm2 = Worm::new(head_position, [East, East, East, North, East, South, East, Down, West, West, Up, South, West]);
+        assert_contiguous(&worm1);
         let segs1 = worm1.segment_positions();
         let segs2 = worm2.segment_positions();
         for (seg1, seg2) in segs1.zip(segs2) {
             assert_eq!(seg1, seg2);
         }
     }
+
+    #[test]
+    fn test_invalid() {
+        let error = Worm::from_str(Vector3i::new(0, 0, 0), ">>?").unwrap_err();
+        assert_eq!(error, WormParseError('?'));
+        let error: Box<dyn std::error::Error> = Box::new(error);
+        assert_eq!(error.to_string(), "invalid character: '?'");
+    }
 }
 
 #[cfg(test)]
@@ -95,3 +112,783 @@ mod segment_positions {
         }
     }
 }
+
+mod builder {
+    use super::*;
+    use crate::worm::builder::WormBuilder;
+
+    #[test]
+    fn test_same_as_new() {
+        use Direction3::*;
+        const HEAD_POS: Vector3i = Vector3i { x: 1, y: 2, z: 3 };
+        let built = WormBuilder::head(HEAD_POS)
+            .segments([East, East, North])
+            .build();
+        assert_eq!(built, Worm::new(HEAD_POS, [East, East, North]));
+    }
+
+    #[test]
+    fn test_extend() {
+        use Direction3::*;
+        const HEAD_POS: Vector3i = Vector3i { x: 0, y: 0, z: 0 };
+        let mut builder = WormBuilder::head(HEAD_POS).segments([Up]);
+        builder.extend([West, South]);
+        builder.extend([Down]);
+        assert_eq!(builder.build(), Worm::new(HEAD_POS, [Up, West, South, Down]));
+    }
+
+    #[test]
+    fn test_tailless() {
+        const HEAD_POS: Vector3i = Vector3i { x: 4, y: 0, z: 0 };
+        let worm = WormBuilder::head(HEAD_POS).build();
+        assert!(worm.is_tailless(), "builder without segments should produce a tailless worm");
+        assert_eq!(worm, Worm::new(HEAD_POS, []));
+    }
+}
+
+mod crawl_wrapping {
+    use super::*;
+
+    #[test]
+    fn test_east_edge() {
+        use Direction3::*;
+        let bounds = Vector3i::new(4, 4, 1);
+        let mut worm = Worm::new(Vector3i::new(3, 1, 0), [West, West]);
+        worm.crawl_wrapping(East, bounds);
+        assert_eq!(worm.head_position(), Vector3i::new(0, 1, 0), "head should reappear on the west edge");
+        let positions: Vec<_> = worm.segment_positions_wrapping(bounds).collect();
+        assert_eq!(positions, [
+            Vector3i::new(0, 1, 0),
+            Vector3i::new(3, 1, 0),
+            Vector3i::new(2, 1, 0),
+        ], "body should follow the head across the edge");
+    }
+
+    #[test]
+    fn test_one_wide() {
+        use Direction3::*;
+        let bounds = Vector3i::new(4, 4, 1);
+        let mut worm = Worm::new(Vector3i::new(1, 1, 0), [West]);
+        worm.crawl_wrapping(Up, bounds);
+        assert_eq!(worm.head_position(), Vector3i::new(1, 1, 0), "1-wide dimension should wrap onto itself");
+        for position in worm.segment_positions_wrapping(bounds) {
+            assert_eq!(position.z, 0, "body should stay in the only layer");
+        }
+    }
+}
+
+mod mirror {
+    use super::*;
+
+    #[test]
+    fn test_twice_is_identity() {
+        use Direction3::*;
+        let original = Worm::new(Vector3i::new(2, 3, 4), [East, North, Up, Up, West]);
+        for axis in Axis3i::ALL {
+            let mut worm = original.clone();
+            worm.mirror(axis);
+            assert_ne!(worm, original);
+            worm.mirror(axis);
+            assert_eq!(worm, original, "mirroring twice across {axis:?} should restore the worm");
+        }
+    }
+
+    #[test]
+    fn test_flips_body() {
+        use Direction3::*;
+        let mut worm = Worm::new(Vector3i::new(0, 0, 0), [East, North]);
+        worm.mirror(Axis3i::X);
+        assert_eq!(worm.segment_positions().collect::<Vec<_>>(), [
+            Vector3i::new(0, 0, 0),
+            Vector3i::new(-1, 0, 0),
+            Vector3i::new(-1, 1, 0),
+        ]);
+    }
+}
+
+mod rotate {
+    use super::*;
+
+    #[test]
+    fn test_full_turn() {
+        use Direction3::*;
+        let original = Worm::new(Vector3i::new(2, 3, 4), [East, North, Up, Up, West]);
+        for axis in Axis3i::ALL {
+            let mut worm = original.clone();
+            for _ in 0..4 {
+                worm.rotate(axis, 1);
+            }
+            assert!(worm.segment_positions().eq(original.segment_positions()), "4 quarter turns about {axis:?} should restore the worm");
+        }
+    }
+
+    #[test]
+    fn test_quarter_turn() {
+        use Direction3::*;
+        let mut worm = Worm::new(Vector3i::new(0, 0, 0), [East, North]);
+        worm.rotate(Axis3i::Z, 1);
+        let positions: Vec<_> = worm.segment_positions().collect();
+        assert_eq!(positions, [
+            Vector3i::new(0, 0, 0),
+            Vector3i::new(0, -1, 0),
+            Vector3i::new(1, -1, 0),
+        ], "body should swing clockwise about the head");
+    }
+}
+
+#[cfg(feature = "raylib")]
+mod segment_world_positions {
+    use super::*;
+
+    #[test]
+    fn test_matches_cell_to_world() {
+        use Direction3::*;
+        let worm = Worm::new(Vector3i::new(-2, 3, 1), [East, North, Up, West]);
+        for config in [RenderConfig::default(), RenderConfig { cell_size: 2.5 }] {
+            let expected = worm.segment_positions().map(|cell| config.cell_to_world(cell));
+            assert!(worm.segment_world_positions(&config).eq(expected));
+            assert_eq!(worm.head_world_position(&config), config.cell_to_world(worm.head_position()));
+        }
+    }
+}
+
+mod body_distance {
+    use super::*;
+
+    #[test]
+    fn test_u_shape() {
+        use Direction3::*;
+        /*******
+         * o . *
+         * | | *
+         * | | *
+         * '-' *
+         *******/
+        let worm = Worm::new(Vector3i::new(0, 0, 0), [South, South, South, East, North, North, North]);
+        let positions: Vec<_> = worm.segment_positions().collect();
+        assert_eq!(positions[7], Vector3i::new(1, 0, 0), "tail should end beside the head");
+        assert_eq!(worm.body_distance(0, 7), 7, "head and tail are spatially adjacent but far along the body");
+        assert_eq!(worm.body_distance(7, 0), 7, "distance should be symmetric");
+        assert_eq!(worm.body_distance(3, 3), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_range() {
+        let worm = Worm::new(Vector3i::new(0, 0, 0), [Direction3::East]);
+        worm.body_distance(0, 2);
+    }
+}
+
+mod is_loop {
+    use super::*;
+
+    #[test]
+    fn test_square() {
+        use Direction3::*;
+        let worm = Worm::new(Vector3i::new(0, 0, 0), [East, North, West]);
+        assert!(worm.is_loop(), "2x2 square should be a loop");
+        let worm = Worm::new(Vector3i::new(0, 0, 0), [East, East, North, North, West, West, South]);
+        assert!(worm.is_loop(), "3x3 ring should be a loop");
+    }
+
+    #[test]
+    fn test_straight() {
+        use Direction3::*;
+        let worm = Worm::new(Vector3i::new(0, 0, 0), [East, East, East]);
+        assert!(!worm.is_loop());
+    }
+
+    #[test]
+    fn test_short() {
+        assert!(!Worm::new(Vector3i::new(0, 0, 0), []).is_loop(), "tailless worm is not a loop");
+        assert!(!Worm::new(Vector3i::new(0, 0, 0), [Direction3::East]).is_loop(), "single segment is not a loop");
+    }
+
+    #[test]
+    fn test_overlapping() {
+        use Direction3::*;
+        let worm = Worm::new(Vector3i::new(0, 0, 0), [East, North, West, South, East]);
+        assert!(!worm.is_loop(), "worm crossing itself is not a clean loop");
+    }
+}
+
+mod split_at {
+    use super::*;
+
+    #[test]
+    fn test_middle() {
+        use Direction3::*;
+        let worm = Worm::new(Vector3i::new(0, 0, 0), [East, East, North, North]);
+        let (head_piece, tail_piece) = worm.split_at(2);
+        assert_eq!(head_piece, Worm::new(Vector3i::new(0, 0, 0), [East]));
+        assert_eq!(tail_piece, Some(Worm::new(Vector3i::new(2, 0, 0), [North, North])));
+    }
+
+    #[test]
+    fn test_whole() {
+        use Direction3::*;
+        let worm = Worm::new(Vector3i::new(0, 0, 0), [East, North]);
+        let (head_piece, tail_piece) = worm.split_at(3);
+        assert_eq!(head_piece, worm, "splitting past the tail should keep the whole worm");
+        assert_eq!(tail_piece, None);
+    }
+}
+
+mod segment_positions_rev {
+    use super::*;
+
+    #[test]
+    fn test_reversed() {
+        use Direction3::*;
+        let worm = Worm::new(Vector3i::new(2, 9, 1), [North, North, East, Up, West, West, Down]);
+        let mut forward: Vec<_> = worm.segment_positions().collect();
+        forward.reverse();
+        assert!(worm.segment_positions_rev().eq(forward));
+    }
+
+    #[test]
+    fn test_tailless() {
+        let worm = Worm::new(Vector3i::new(5, 3, 8), []);
+        assert!(worm.segment_positions_rev().eq([Vector3i::new(5, 3, 8)]));
+    }
+}
+
+mod crawl {
+    use super::*;
+
+    #[test]
+    fn test_random_stays_contiguous() {
+        let mut worm = Worm::new(Vector3i::new(0, 0, 0), [Direction3::West; 6]);
+        // xorshift, so the sequence is long and varied but the same every run
+        let mut state = 0x2545_f491_u32;
+        for step in 0..1000 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let direction = Direction3::ALL[state as usize % Direction3::ALL.len()];
+            worm.crawl(direction);
+            assert_eq!(worm.num_segments(), 7, "length should be preserved at step {step}");
+            assert_contiguous(&worm);
+        }
+    }
+}
+
+mod neck_position {
+    use super::*;
+
+    #[test]
+    fn test_known() {
+        let worm = Worm::from_str(Vector3i::new(2, 2, 0), "^>>").unwrap();
+        assert_eq!(worm.neck_position(), Some(Vector3i::new(2, 3, 0)));
+        assert_eq!(worm.neck_position(), worm.segment_positions().nth(1));
+        assert_eq!(worm.cell_ahead(Direction3::West), Vector3i::new(1, 2, 0));
+        assert_eq!(worm.cell_ahead(Direction3::Up), Vector3i::new(2, 2, 1));
+    }
+
+    #[test]
+    fn test_tailless() {
+        let worm = Worm::new(Vector3i::new(2, 2, 0), []);
+        assert_eq!(worm.neck_position(), None);
+        assert_eq!(worm.cell_ahead(Direction3::North), Vector3i::new(2, 3, 0));
+    }
+}
+
+mod grow_by {
+    use super::*;
+
+    #[test]
+    fn test_grow() {
+        let mut worm = Worm::from_str(Vector3i::new(0, 0, 0), "<<").unwrap();
+        worm.grow_by(3);
+        assert_eq!(worm.num_segments(), 6);
+        assert_eq!(worm.segment_positions().last(), Some(Vector3i::new(-5, 0, 0)), "tail should keep growing straight back");
+    }
+
+    #[test]
+    fn test_grow_tailless() {
+        let mut worm = Worm::new(Vector3i::new(0, 0, 0), []);
+        worm.crawl(Direction3::North);
+        worm.grow_by(3);
+        assert_eq!(worm.num_segments(), 4);
+        assert_eq!(worm.segment_positions().last(), Some(Vector3i::new(0, -2, 0)), "tailless worm should grow behind the way it faces");
+        assert_eq!(worm.facing(), Direction3::North);
+    }
+
+    #[test]
+    fn test_shrink() {
+        let mut worm = Worm::from_str(Vector3i::new(0, 0, 0), "<<^").unwrap();
+        worm.shrink_by(2);
+        assert_eq!(worm.num_segments(), 2);
+        assert_eq!(worm.segment_positions().last(), Some(Vector3i::new(-1, 0, 0)));
+    }
+
+    #[test]
+    fn test_shrink_past_length() {
+        let mut worm = Worm::from_str(Vector3i::new(0, 0, 0), "<<").unwrap();
+        worm.shrink_by(10);
+        assert!(worm.is_tailless());
+        assert_eq!(worm.head_position(), Vector3i::new(0, 0, 0));
+    }
+}
+
+mod translate {
+    use super::*;
+
+    #[test]
+    fn test_shifts_every_segment() {
+        let original = Worm::from_str(Vector3i::new(1, 2, 3), "<<^x>").unwrap();
+        let delta = Vector3i::new(-4, 7, 2);
+        let mut moved = original.clone();
+        moved.translate(delta);
+        let expected: Vec<_> = original.segment_positions().map(|cell| cell + delta).collect();
+        assert_eq!(moved.segment_positions().collect::<Vec<_>>(), expected);
+        assert!(moved.segment_directions().eq(original.segment_directions()), "shape should be unchanged");
+    }
+}
+
+mod segment_directions_contiguous {
+    use super::*;
+
+    #[test]
+    fn test_matches_iter() {
+        let mut worm = Worm::from_str(Vector3i::new(0, 0, 0), "<<^^>").unwrap();
+        // Crawling pushes at the front and pops at the back, so the ring buffer wraps
+        for direction in [Direction3::East, Direction3::South, Direction3::East, Direction3::East] {
+            worm.crawl(direction);
+        }
+        let expected: Vec<_> = worm.segment_directions().collect();
+        let before = worm.clone();
+        assert_eq!(worm.segment_directions_contiguous(), expected);
+        assert_eq!(worm, before, "rearranging memory should not change the worm");
+    }
+
+    #[test]
+    fn test_tailless() {
+        let mut worm = Worm::new(Vector3i::new(0, 0, 0), []);
+        assert!(worm.segment_directions_contiguous().is_empty());
+    }
+}
+
+mod same_shape {
+    use super::*;
+
+    #[test]
+    fn test_reversed() {
+        let worm = Worm::from_str(Vector3i::new(0, 0, 0), ">>^<x").unwrap();
+        let mut reversed = worm.clone();
+        reversed.reverse();
+        assert_eq!(reversed.head_position(), worm.segment_positions().last().unwrap());
+        assert!(reversed.segment_positions().eq(worm.segment_positions_rev()));
+        assert!(worm.same_shape(&reversed));
+        assert_ne!(worm, reversed);
+    }
+
+    #[test]
+    fn test_different_cells() {
+        let worm = Worm::from_str(Vector3i::new(0, 0, 0), ">>^").unwrap();
+        let other = Worm::from_str(Vector3i::new(0, 0, 0), ">>v").unwrap();
+        assert!(!worm.same_shape(&other));
+    }
+}
+
+mod crawl_growing {
+    use super::*;
+
+    #[test]
+    fn test_traces_path() {
+        let start = Vector3i::new(0, 0, 0);
+        let mut worm = Worm::new(start, []);
+        let path = [Direction3::East, Direction3::East, Direction3::North, Direction3::Up, Direction3::West];
+        for (i, &direction) in path.iter().enumerate() {
+            worm.crawl_growing(direction);
+            assert_eq!(worm.num_segments(), i + 2);
+            assert_contiguous(&worm);
+        }
+        let visited: Vec<_> = path.iter()
+            .scan(start, |cell, &direction| {
+                *cell += direction;
+                Some(*cell)
+            })
+            .collect();
+        let expected: Vec<_> = std::iter::once(start).chain(visited).collect();
+        assert_eq!(worm.segment_positions_rev().collect::<Vec<_>>(), expected);
+    }
+}
+
+mod errors {
+    use super::*;
+
+    #[test]
+    fn test_boxed() {
+        let mut worm = Worm::new(Vector3i::new(0, 0, 0), []);
+        let error: Box<dyn std::error::Error> = Box::new(worm.try_lengthen().unwrap_err());
+        assert_eq!(error.to_string(), "missing tail to lengthen, need direction");
+        let error: Box<dyn std::error::Error> = Box::new(CrawlBlocked::Solid(Vector3i::new(1, 2, 3)));
+        assert_eq!(error.to_string(), "(1, 2, 3) is solid");
+    }
+}
+
+mod slide {
+    use super::*;
+
+    #[test]
+    fn test_stops_at_wall() {
+        let mut level = Level::new(Vector3i::new(8, 1, 1));
+        level.add_wall(Vector3i::new(6, 0, 0));
+        let mut worm = Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]);
+        assert_eq!(worm.slide(Direction3::East, &level, &Ruleset::new()), 4);
+        assert_eq!(worm, Worm::new(Vector3i::new(5, 0, 0), [Direction3::West]));
+    }
+
+    #[test]
+    fn test_blocked_immediately() {
+        let mut level = Level::new(Vector3i::new(8, 1, 1));
+        level.add_wall(Vector3i::new(2, 0, 0));
+        let mut worm = Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]);
+        assert_eq!(worm.slide(Direction3::East, &level, &Ruleset::new()), 0);
+        assert_eq!(worm, Worm::new(Vector3i::new(1, 0, 0), [Direction3::West]));
+    }
+}
+
+mod clamp_to_bounds {
+    use super::*;
+
+    #[test]
+    fn test_partly_outside() {
+        let mut worm = Worm::from_str(Vector3i::new(-1, 6, 0), ">>^").unwrap();
+        assert_eq!(worm.bounding_box(), (Vector3i::new(-1, 6, 0), Vector3i::new(1, 7, 0)));
+        assert!(worm.clamp_to_bounds(Vector3i::new(0, 0, 0), Vector3i::new(5, 5, 0)));
+        assert_eq!(worm.bounding_box(), (Vector3i::new(0, 4, 0), Vector3i::new(2, 5, 0)));
+        assert!(!worm.clamp_to_bounds(Vector3i::new(0, 0, 0), Vector3i::new(5, 5, 0)), "already inside");
+    }
+
+    #[test]
+    fn test_too_large() {
+        let mut worm = Worm::from_str(Vector3i::new(-1, 0, 0), ">>>>").unwrap();
+        let before = worm.clone();
+        assert!(!worm.clamp_to_bounds(Vector3i::new(0, 0, 0), Vector3i::new(2, 2, 2)));
+        assert_eq!(worm, before);
+    }
+}
+
+mod try_lengthen_in {
+    use super::*;
+
+    #[test]
+    fn test_into_wall() {
+        let mut level = Level::new(Vector3i::new(8, 1, 1));
+        level.add_wall(Vector3i::new(0, 0, 0));
+        let mut worm = Worm::from_str(Vector3i::new(2, 0, 0), "<").unwrap();
+        let before = worm.clone();
+        assert_eq!(worm.try_lengthen_in(&level, &Ruleset::new()), Err(GrowBlocked::Solid(Vector3i::new(0, 0, 0))));
+        assert_eq!(worm, before);
+    }
+
+    #[test]
+    fn test_open() {
+        let level = Level::new(Vector3i::new(8, 1, 1));
+        let mut worm = Worm::from_str(Vector3i::new(3, 0, 0), "<").unwrap();
+        assert_eq!(worm.try_lengthen_in(&level, &Ruleset::new()), Ok(()));
+        assert_eq!(worm.segment_positions().last(), Some(Vector3i::new(1, 0, 0)));
+        assert_eq!(worm.try_lengthen_in(&level, &Ruleset::new()), Ok(()));
+        assert_eq!(worm.try_lengthen_in(&level, &Ruleset::new()), Err(GrowBlocked::OutOfBounds(Vector3i::new(-1, 0, 0))));
+    }
+
+    #[test]
+    fn test_tailless() {
+        let level = Level::new(Vector3i::new(8, 1, 1));
+        let mut worm = Worm::new(Vector3i::new(3, 0, 0), []);
+        assert_eq!(worm.try_lengthen_in(&level, &Ruleset::new()), Err(GrowBlocked::Tailless));
+    }
+}
+
+mod overlaps {
+    use super::*;
+
+    #[test]
+    fn test_crossing() {
+        let a = Worm::from_str(Vector3i::new(0, 1, 0), ">>>").unwrap();
+        let b = Worm::from_str(Vector3i::new(2, 0, 0), "^^").unwrap();
+        assert!(a.overlaps(&b));
+        assert!(b.overlaps(&a));
+    }
+
+    #[test]
+    fn test_disjoint() {
+        let a = Worm::from_str(Vector3i::new(0, 1, 0), ">>>").unwrap();
+        let b = Worm::from_str(Vector3i::new(0, 0, 0), ">>>").unwrap();
+        assert!(!a.overlaps(&b));
+    }
+
+    #[test]
+    fn test_shared_tail() {
+        let a = Worm::from_str(Vector3i::new(0, 0, 0), ">>").unwrap();
+        let b = Worm::from_str(Vector3i::new(2, 3, 0), "vvv").unwrap();
+        assert!(a.overlaps(&b));
+    }
+}
+
+mod occupied_cells {
+    use super::*;
+
+    #[test]
+    fn test_not_stale() {
+        let mut worm = Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap();
+        assert!(worm.occupies(Vector3i::new(0, 0, 0)), "builds the cache");
+        worm.crawl(Direction3::East);
+        let expected: HashSet<_> = worm.segment_positions().collect();
+        assert_eq!(worm.occupied_cells(), &expected);
+        assert!(!worm.occupies(Vector3i::new(0, 0, 0)), "the old tail cell should be forgotten");
+    }
+
+    #[test]
+    fn test_ignored_by_eq() {
+        let worm = Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap();
+        let cached = worm.clone();
+        cached.occupied_cells();
+        assert_eq!(worm, cached);
+    }
+}
+
+mod facing {
+    use super::*;
+
+    #[test]
+    fn test_tailless() {
+        let mut worm = Worm::new(Vector3i::new(0, 0, 0), []);
+        assert_eq!(worm.facing(), Direction3::East);
+        worm.crawl(Direction3::North);
+        assert_eq!(worm.facing(), Direction3::North);
+        assert_ne!(worm, Worm::new(Vector3i::new(0, 1, 0), []), "facing should be compared while tailless");
+        worm.reverse();
+        assert_eq!(worm.facing(), Direction3::South);
+    }
+
+    #[test]
+    fn test_bodied() {
+        let mut worm = Worm::from_str(Vector3i::new(1, 0, 0), "<").unwrap();
+        assert_eq!(worm.facing(), Direction3::East);
+        worm.crawl(Direction3::North);
+        assert_eq!(worm.facing(), Direction3::North);
+        worm.shrink_by(1);
+        assert_eq!(worm.facing(), Direction3::North, "losing the neck should keep the facing");
+    }
+}
+
+mod head_ray {
+    use super::*;
+
+    #[test]
+    fn test_head_ray() {
+        let worm = Worm::from_str(Vector3i::new(2, 3, 0), "v<").unwrap();
+        assert_eq!(worm.head_ray(), (Vector3i::new(2, 3, 0), Direction3::North));
+    }
+}
+
+mod collect_positions_into {
+    use super::*;
+
+    #[test]
+    fn test_matches_iterator() {
+        let worm = Worm::from_str(Vector3i::new(0, 0, 0), ">>^^<").unwrap();
+        let mut buf = vec![Vector3i::new(9, 9, 9)];
+        worm.collect_positions_into(&mut buf);
+        assert_eq!(buf, worm.segment_positions().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_reuses_capacity() {
+        let long = Worm::from_str(Vector3i::new(0, 0, 0), ">>>>>>>>").unwrap();
+        let short = Worm::from_str(Vector3i::new(0, 0, 0), "^^").unwrap();
+        let mut buf = Vec::new();
+        long.collect_positions_into(&mut buf);
+        let (capacity, pointer) = (buf.capacity(), buf.as_ptr());
+        for worm in [&short, &long, &short] {
+            worm.collect_positions_into(&mut buf);
+            assert_eq!(buf.len(), worm.num_segments());
+            assert_eq!((buf.capacity(), buf.as_ptr()), (capacity, pointer), "the buffer should not be reallocated");
+        }
+    }
+}
+
+mod centroid {
+    use super::*;
+
+    #[test]
+    fn test_symmetric() {
+        let worm = Worm::from_str(Vector3i::new(0, 2, 0), "vv>>^^").unwrap();
+        assert_eq!(worm.centroid(), Vector3i::new(1, 1, 0), "a U shape should be centered between its arms");
+        let worm = Worm::from_str(Vector3i::new(-3, 5, 1), ">>").unwrap();
+        assert_eq!(worm.centroid(), Vector3i::new(-2, 5, 1));
+    }
+
+    #[test]
+    fn test_rounding() {
+        assert_eq!(Worm::from_str(Vector3i::new(0, 0, 0), ">").unwrap().centroid(), Vector3i::new(1, 0, 0), "halves round up");
+        assert_eq!(Worm::from_str(Vector3i::new(-1, 0, 0), ">").unwrap().centroid(), Vector3i::new(0, 0, 0), "halves round up past zero too");
+        assert_eq!(Worm::from_str(Vector3i::new(0, 0, 0), ">>>").unwrap().centroid(), Vector3i::new(2, 0, 0));
+    }
+
+    #[test]
+    fn test_tailless() {
+        assert_eq!(Worm::new(Vector3i::new(4, -2, 7), []).centroid(), Vector3i::new(4, -2, 7));
+    }
+}
+
+mod trim_to {
+    use super::*;
+
+    #[test]
+    fn test_trim() {
+        let mut worm = Worm::from_str(Vector3i::new(0, 0, 0), ">>^^").unwrap();
+        let cells: Vec<_> = worm.segment_positions().collect();
+        worm.trim_to(3);
+        assert_eq!(worm.segment_positions().collect::<Vec<_>>(), cells[..3]);
+    }
+
+    #[test]
+    fn test_already_short() {
+        let mut worm = Worm::from_str(Vector3i::new(0, 0, 0), ">>").unwrap();
+        let before = worm.clone();
+        worm.trim_to(5);
+        assert_eq!(worm, before);
+    }
+
+    #[test]
+    fn test_tailless() {
+        let mut worm = Worm::from_str(Vector3i::new(0, 0, 0), ">>").unwrap();
+        worm.trim_to(1);
+        assert!(worm.is_tailless());
+        assert_eq!(worm.facing(), Direction3::West, "the head should keep facing the same way");
+        worm.trim_to(0);
+        assert_eq!(worm.num_segments(), 1, "the head is never removed");
+    }
+}
+
+mod undo_crawl {
+    use super::*;
+
+    fn worm(segments: &str) -> Worm {
+        let mut worm = Worm::from_str(Vector3i::new(0, 0, 0), segments).unwrap();
+        worm.set_crawl_history_limit(8);
+        worm
+    }
+
+    #[test]
+    fn test_restores_positions() {
+        use Direction3::*;
+        for (segments, crawls) in [
+            (">>^", [North, West, North]),
+            (">", [West, East, South]),
+            ("", [North, East, East]),
+            ("<v", [East, West, North]),
+        ] {
+            let mut worm = worm(segments);
+            let mut history = vec![worm.clone()];
+            for direction in crawls {
+                worm.crawl(direction);
+                assert_contiguous(&worm);
+                history.push(worm.clone());
+            }
+            history.pop();
+            while let Some(before) = history.pop() {
+                assert!(worm.undo_crawl());
+                assert!(worm.segment_positions().eq(before.segment_positions()), "{segments:?} after {crawls:?}");
+                assert_eq!(worm, before, "facing should be restored too");
+            }
+            assert!(!worm.undo_crawl());
+        }
+    }
+
+    #[test]
+    fn test_off_by_default() {
+        let mut worm = Worm::from_str(Vector3i::new(0, 0, 0), ">").unwrap();
+        worm.crawl(Direction3::North);
+        assert!(!worm.undo_crawl());
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut worm = worm(">");
+        worm.set_crawl_history_limit(2);
+        for _ in 0..5 {
+            worm.crawl(Direction3::North);
+        }
+        assert!(worm.undo_crawl() && worm.undo_crawl());
+        assert!(!worm.undo_crawl(), "only the last 2 crawls should be remembered");
+    }
+
+    #[test]
+    fn test_other_change_forgets() {
+        let mut worm = worm(">");
+        worm.crawl(Direction3::North);
+        worm.translate(Vector3i::new(1, 0, 0));
+        assert!(!worm.undo_crawl(), "a crawl can't be reverted past a translation");
+    }
+}
+
+mod try_crawl_in {
+    use super::*;
+
+    #[test]
+    fn test_vacated() {
+        let level = Level::new(Vector3i::new(8, 8, 1));
+        let rules = Ruleset::new();
+        let mut worm = Worm::from_str(Vector3i::new(3, 3, 0), "<<").unwrap();
+        let outcome = worm.try_crawl_in(Direction3::East, &level, &rules).unwrap();
+        assert_eq!(outcome.vacated, Some(Vector3i::new(1, 3, 0)), "straight crawl leaves the old tail");
+        let outcome = worm.try_crawl_in(Direction3::West, &level, &rules).unwrap();
+        assert_eq!(outcome.vacated, None, "reversing");
+        let mut tailless = Worm::new(Vector3i::new(3, 3, 0), []);
+        let outcome = tailless.try_crawl_in(Direction3::North, &level, &rules).unwrap();
+        assert_eq!(outcome.vacated, Some(Vector3i::new(3, 3, 0)));
+    }
+
+    #[test]
+    fn test_chasing_tail() {
+        let level = Level::new(Vector3i::new(8, 8, 1));
+        let mut worm = Worm::from_str(Vector3i::new(3, 3, 0), "<v>").unwrap();
+        let outcome = worm.try_crawl_in(Direction3::South, &level, &Ruleset::new()).unwrap();
+        assert_eq!(outcome.vacated, None, "the head moved into the cell the tail left");
+    }
+}
+
+mod segments {
+    use super::*;
+
+    #[test]
+    fn test_tailless() {
+        let worm = Worm::new(Vector3i::new(0, 0, 0), []);
+        let segments = worm.segments();
+        assert!(segments.is_empty());
+        assert_eq!(segments.len(), 0);
+        assert_eq!(segments.head_direction(), None);
+        assert_eq!(segments.tail_direction(), None);
+        assert_eq!(segments.get(0), None);
+        assert_eq!(segments.iter().next(), None);
+    }
+
+    #[test]
+    fn test_bodied() {
+        use Direction3::*;
+        let worm = Worm::from_str(Vector3i::new(0, 0, 0), ">^^x").unwrap();
+        let segments = worm.segments();
+        assert!(!segments.is_empty());
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments.head_direction(), Some(East));
+        assert_eq!(segments.tail_direction(), Some(Up));
+        assert_eq!((segments[0], segments[2]), (East, North));
+        assert_eq!(segments.get(4), None);
+        assert_eq!(segments.iter().len(), 4);
+        assert_eq!(segments.into_iter().collect::<Vec<_>>(), [East, North, North, Up]);
+        assert_eq!(segments.iter().rev().collect::<Vec<_>>(), [Up, North, North, East]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_tailless() {
+        let worm = Worm::new(Vector3i::new(0, 0, 0), []);
+        let _ = worm.segments()[0];
+    }
+}
@@ -0,0 +1,26 @@
+//! The level files in `levels/` parse, round-trip, and can be won.
+
+use puzzle_game_rs::{game_state::GameState, solver::solve};
+
+fn check(text: &str, max_depth: usize) {
+    let game: GameState = text.parse().expect("level file should parse");
+    assert!(!game.is_won(), "level should not start won");
+    assert_eq!(game.to_string().parse::<GameState>(), Ok(game.clone()), "level should round-trip");
+
+    let solution = solve(&game, max_depth).expect("level should be solvable");
+    let mut replay = game;
+    for direction in solution {
+        replay.step(direction);
+    }
+    assert!(replay.is_won(), "solution should win when replayed");
+}
+
+#[test]
+fn test_corridor() {
+    check(include_str!("../levels/corridor.txt"), 8);
+}
+
+#[test]
+fn test_finish_the_rule() {
+    check(include_str!("../levels/finish_the_rule.txt"), 8);
+}